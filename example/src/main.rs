@@ -1,13 +1,27 @@
 use gtk::{glib::ExitCode, prelude::*};
-use rtsp_player_rs::init_gst;
-mod glarea;
+use rtsp_player_gtk::RtspVideoWidget;
+use rtsp_player_rs::{Credentials, PlayerConfig};
 
 fn main() -> ExitCode {
     let app = gtk::Application::builder()
         .application_id("org.example.HelloWorld")
         .build();
     app.connect_activate(|app| {
-        init_gst().unwrap();
+        rtsp_player_gtk::init().unwrap();
+
+        let uri =
+            std::env::var("RTSP_URI").unwrap_or_else(|_| "rtsp://localhost:8554/test".to_string());
+        let mut config = PlayerConfig::builder();
+        if let (Ok(user), Ok(password)) =
+            (std::env::var("RTSP_USER"), std::env::var("RTSP_PASSWORD"))
+        {
+            config = config.credentials(Credentials::new(user, password));
+        }
+        let widget = RtspVideoWidget::new(config.build());
+        widget.set_uri(Some(uri));
+        if let Err(e) = widget.play() {
+            eprintln!("Failed to start RTSP pipeline: {:?}", e);
+        }
 
         // We create the main window.
 
@@ -25,4 +39,3 @@ fn main() -> ExitCode {
 
     app.run()
 }
-
@@ -34,9 +34,10 @@ class _MyAppState extends State<MyApp> {
               ElevatedButton(
                 onPressed: () async {
                   try {
-                    final res = await flutter_gstreamer.helloWorld();
+                    final session = flutter_gstreamer.createSession();
+                    await flutter_gstreamer.dispose(session: session);
                     setState(() {
-                      fromRust = res;
+                      fromRust = 'created and disposed a session';
                     });
                   } catch (e, st) {
                     print(e);
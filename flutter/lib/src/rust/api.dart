@@ -6,4 +6,346 @@
 import 'frb_generated.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 
-Future<String> helloWorld() => RustLib.instance.api.crateApiHelloWorld();
+
+            // These functions are ignored because they are not marked as `pub`: `parse_track_id`, `to_chrono`, `track_id`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `drop`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`
+
+
+            /// Creates a session. `config_json` is a serialised `PlayerConfig` preset,
+/// `None` uses the defaults.
+SessionId createSession({String? configJson }) => RustLib.instance.api.crateApiCreateSession(configJson: configJson);
+
+Future<void> open({required SessionId session , required String uri }) => RustLib.instance.api.crateApiOpen(session: session, uri: uri);
+
+Future<void> play({required SessionId session }) => RustLib.instance.api.crateApiPlay(session: session);
+
+Future<void> pause({required SessionId session }) => RustLib.instance.api.crateApiPause(session: session);
+
+Future<void> stop({required SessionId session }) => RustLib.instance.api.crateApiStop(session: session);
+
+Future<void> dispose({required SessionId session }) => RustLib.instance.api.crateApiDispose(session: session);
+
+/// Volume in percent, 100 leaving the audio unchanged.
+Future<void> setVolume({required SessionId session , required double volume }) => RustLib.instance.api.crateApiSetVolume(session: session, volume: volume);
+
+Future<void> setMute({required SessionId session , required bool muted }) => RustLib.instance.api.crateApiSetMute(session: session, muted: muted);
+
+Future<void> setAudioDevice({required SessionId session , required AudioDevice device }) => RustLib.instance.api.crateApiSetAudioDevice(session: session, device: device);
+
+/// The last rendered frame, encoded with EXIF tags. `quality` only applies
+/// to JPEG.
+Future<Uint8List> snapshot({required SessionId session , required SnapshotFormat format , required int quality }) => RustLib.instance.api.crateApiSnapshot(session: session, format: format, quality: quality);
+
+/// Starts recording the stream. `config_json` is a serialised
+/// `RecordingConfig`.
+Future<void> startRecording({required SessionId session , required String configJson }) => RustLib.instance.api.crateApiStartRecording(session: session, configJson: configJson);
+
+Future<void> stopRecording({required SessionId session }) => RustLib.instance.api.crateApiStopRecording(session: session);
+
+/// Saves the buffered `pre` plus the following `post` of the stream to an
+/// MP4 at `path`. Needs `prebuffer` in the session's config.
+Future<void> saveClip({required SessionId session , required Duration pre , required Duration post , required String path }) => RustLib.instance.api.crateApiSaveClip(session: session, pre: pre, post: post, path: path);
+
+/// Sets the main stream and substreams of the camera, see
+/// `set_render_size`.
+Future<void> setStreamVariants({required SessionId session , required List<StreamVariant> variants }) => RustLib.instance.api.crateApiSetStreamVariants(session: session, variants: variants);
+
+/// Reports the size the video is shown at, in physical pixels, so the
+/// smallest stream variant covering it is played.
+Future<void> setRenderSize({required SessionId session , required int width , required int height }) => RustLib.instance.api.crateApiSetRenderSize(session: session, width: width, height: height);
+
+/// Selects a video track by id: `auto`, `no` or a track id from `Tracks`.
+Future<void> selectVideoTrack({required SessionId session , required String id }) => RustLib.instance.api.crateApiSelectVideoTrack(session: session, id: id);
+
+/// Selects an audio track by id, like `select_video_track`.
+Future<void> selectAudioTrack({required SessionId session , required String id }) => RustLib.instance.api.crateApiSelectAudioTrack(session: session, id: id);
+
+/// The available audio outputs, starting with the automatic one.
+Future<List<AudioDevice>> audioDevices() => RustLib.instance.api.crateApiAudioDevices();
+
+/// Points the session at the texture registered by the Dart side.
+Future<void> setTexture({required SessionId session , required BigInt texturePtr , required int width , required int height }) => RustLib.instance.api.crateApiSetTexture(session: session, texturePtr: texturePtr, width: width, height: height);
+
+Stream<PlayerState> stateStream({required SessionId session }) => RustLib.instance.api.crateApiStateStream(session: session);
+
+/// Link statistics, refreshed every second.
+Stream<StreamStats> statsStream({required SessionId session }) => RustLib.instance.api.crateApiStatsStream(session: session);
+
+            
+                // Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<SessionId>>
+                abstract class SessionId implements RustOpaqueInterface {
+                    
+
+                    
+                }
+                
+
+class AudioDevice  {
+                final String name;
+final String description;
+
+                const AudioDevice({required this.name ,required this.description ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => name.hashCode^description.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is AudioDevice &&
+                runtimeType == other.runtimeType
+                && name == other.name&& description == other.description;
+        
+            }
+
+class AudioParams  {
+                final String? format;
+final int? sampleRate;
+final int? channelCount;
+
+                const AudioParams({this.format ,this.sampleRate ,this.channelCount ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => format.hashCode^sampleRate.hashCode^channelCount.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is AudioParams &&
+                runtimeType == other.runtimeType
+                && format == other.format&& sampleRate == other.sampleRate&& channelCount == other.channelCount;
+        
+            }
+
+/// Mirrors the fields of the Dart `VideoTrack`/`AudioTrack`/`SubtitleTrack`
+/// DTOs that an SDP can fill.
+class MediaTrack  {
+                final String id;
+final String? title;
+final String? language;
+final String? codec;
+final int? w;
+final int? h;
+final double? fps;
+final int? bitrate;
+final int? samplerate;
+final int? channelscount;
+
+                const MediaTrack({required this.id ,this.title ,this.language ,this.codec ,this.w ,this.h ,this.fps ,this.bitrate ,this.samplerate ,this.channelscount ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => id.hashCode^title.hashCode^language.hashCode^codec.hashCode^w.hashCode^h.hashCode^fps.hashCode^bitrate.hashCode^samplerate.hashCode^channelscount.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is MediaTrack &&
+                runtimeType == other.runtimeType
+                && id == other.id&& title == other.title&& language == other.language&& codec == other.codec&& w == other.w&& h == other.h&& fps == other.fps&& bitrate == other.bitrate&& samplerate == other.samplerate&& channelscount == other.channelscount;
+        
+            }
+
+/// Mirrors the Dart `PlayerState` DTO.
+class PlayerState  {
+                final bool playing;
+final bool completed;
+final bool buffering;
+final double bufferingPercentage;
+final Duration position;
+final Duration latency;
+final int? width;
+final int? height;
+/// Size of the texture; the widget should relayout when it changes.
+final int? textureWidth;
+final int? textureHeight;
+final VideoParams videoParams;
+final AudioParams audioParams;
+final BigInt framesRendered;
+final BigInt framesDropped;
+final double volume;
+final bool muted;
+final AudioDevice audioDevice;
+final Track track;
+final Tracks tracks;
+
+                const PlayerState({required this.playing ,required this.completed ,required this.buffering ,required this.bufferingPercentage ,required this.position ,required this.latency ,this.width ,this.height ,this.textureWidth ,this.textureHeight ,required this.videoParams ,required this.audioParams ,required this.framesRendered ,required this.framesDropped ,required this.volume ,required this.muted ,required this.audioDevice ,required this.track ,required this.tracks ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => playing.hashCode^completed.hashCode^buffering.hashCode^bufferingPercentage.hashCode^position.hashCode^latency.hashCode^width.hashCode^height.hashCode^textureWidth.hashCode^textureHeight.hashCode^videoParams.hashCode^audioParams.hashCode^framesRendered.hashCode^framesDropped.hashCode^volume.hashCode^muted.hashCode^audioDevice.hashCode^track.hashCode^tracks.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is PlayerState &&
+                runtimeType == other.runtimeType
+                && playing == other.playing&& completed == other.completed&& buffering == other.buffering&& bufferingPercentage == other.bufferingPercentage&& position == other.position&& latency == other.latency&& width == other.width&& height == other.height&& textureWidth == other.textureWidth&& textureHeight == other.textureHeight&& videoParams == other.videoParams&& audioParams == other.audioParams&& framesRendered == other.framesRendered&& framesDropped == other.framesDropped&& volume == other.volume&& muted == other.muted&& audioDevice == other.audioDevice&& track == other.track&& tracks == other.tracks;
+        
+            }
+
+enum SnapshotFormat {
+                    jpeg,
+png,
+                    ;
+                    
+                }
+
+class StreamStats  {
+                final BigInt bitrate;
+final double fpsReceived;
+final double fpsRendered;
+final BigInt packetsReceived;
+final BigInt packetsLost;
+final Duration jitter;
+final Duration latency;
+final BigInt framesDropped;
+
+                const StreamStats({required this.bitrate ,required this.fpsReceived ,required this.fpsRendered ,required this.packetsReceived ,required this.packetsLost ,required this.jitter ,required this.latency ,required this.framesDropped ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => bitrate.hashCode^fpsReceived.hashCode^fpsRendered.hashCode^packetsReceived.hashCode^packetsLost.hashCode^jitter.hashCode^latency.hashCode^framesDropped.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is StreamStats &&
+                runtimeType == other.runtimeType
+                && bitrate == other.bitrate&& fpsReceived == other.fpsReceived&& fpsRendered == other.fpsRendered&& packetsReceived == other.packetsReceived&& packetsLost == other.packetsLost&& jitter == other.jitter&& latency == other.latency&& framesDropped == other.framesDropped;
+        
+            }
+
+class StreamVariant  {
+                final String uri;
+final int width;
+final int height;
+
+                const StreamVariant({required this.uri ,required this.width ,required this.height ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => uri.hashCode^width.hashCode^height.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is StreamVariant &&
+                runtimeType == other.runtimeType
+                && uri == other.uri&& width == other.width&& height == other.height;
+        
+            }
+
+/// Ids of the selected tracks.
+class Track  {
+                final String video;
+final String audio;
+
+                const Track({required this.video ,required this.audio ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => video.hashCode^audio.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is Track &&
+                runtimeType == other.runtimeType
+                && video == other.video&& audio == other.audio;
+        
+            }
+
+class Tracks  {
+                final List<MediaTrack> video;
+final List<MediaTrack> audio;
+final List<MediaTrack> subtitle;
+
+                const Tracks({required this.video ,required this.audio ,required this.subtitle ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => video.hashCode^audio.hashCode^subtitle.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is Tracks &&
+                runtimeType == other.runtimeType
+                && video == other.video&& audio == other.audio&& subtitle == other.subtitle;
+        
+            }
+
+class VideoParams  {
+                final String? pixelformat;
+final int? w;
+final int? h;
+final int? dw;
+final int? dh;
+final double? aspect;
+final double? par;
+final String? colorimetry;
+final double? fps;
+
+                const VideoParams({this.pixelformat ,this.w ,this.h ,this.dw ,this.dh ,this.aspect ,this.par ,this.colorimetry ,this.fps ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => pixelformat.hashCode^w.hashCode^h.hashCode^dw.hashCode^dh.hashCode^aspect.hashCode^par.hashCode^colorimetry.hashCode^fps.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is VideoParams &&
+                runtimeType == other.runtimeType
+                && pixelformat == other.pixelformat&& w == other.w&& h == other.h&& dw == other.dw&& dh == other.dh&& aspect == other.aspect&& par == other.par&& colorimetry == other.colorimetry&& fps == other.fps;
+        
+            }
+            
\ No newline at end of file
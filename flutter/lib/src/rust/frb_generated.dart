@@ -7,205 +7,1320 @@ import 'api.dart';
 import 'dart:async';
 import 'dart:convert';
 import 'frb_generated.dart';
-import 'frb_generated.io.dart'
-    if (dart.library.js_interop) 'frb_generated.web.dart';
+import 'frb_generated.io.dart' if (dart.library.js_interop) 'frb_generated.web.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 
-/// Main entrypoint of the Rust API
-class RustLib extends BaseEntrypoint<RustLibApi, RustLibApiImpl, RustLibWire> {
-  @internal
-  static final instance = RustLib._();
-
-  RustLib._();
-
-  /// Initialize flutter_rust_bridge
-  static Future<void> init({
-    RustLibApi? api,
-    BaseHandler? handler,
-    ExternalLibrary? externalLibrary,
-  }) async {
-    await instance.initImpl(
-      api: api,
-      handler: handler,
-      externalLibrary: externalLibrary,
-    );
-  }
-
-  /// Initialize flutter_rust_bridge in mock mode.
-  /// No libraries for FFI are loaded.
-  static void initMock({
-    required RustLibApi api,
-  }) {
-    instance.initMockImpl(
-      api: api,
-    );
-  }
-
-  /// Dispose flutter_rust_bridge
-  ///
-  /// The call to this function is optional, since flutter_rust_bridge (and everything else)
-  /// is automatically disposed when the app stops.
-  static void dispose() => instance.disposeImpl();
-
-  @override
-  ApiImplConstructor<RustLibApiImpl, RustLibWire> get apiImplConstructor =>
-      RustLibApiImpl.new;
-
-  @override
-  WireConstructor<RustLibWire> get wireConstructor =>
-      RustLibWire.fromExternalLibrary;
-
-  @override
-  Future<void> executeRustInitializers() async {}
-
-  @override
-  ExternalLibraryLoaderConfig get defaultExternalLibraryLoaderConfig =>
-      kDefaultExternalLibraryLoaderConfig;
-
-  @override
-  String get codegenVersion => '2.7.0';
-
-  @override
-  int get rustContentHash => 314717692;
-
-  static const kDefaultExternalLibraryLoaderConfig =
-      ExternalLibraryLoaderConfig(
-    stem: 'flutter_gstreamer',
-    ioDirectory: 'rust/target/release/',
-    webPrefix: 'pkg/',
-  );
-}
-
-abstract class RustLibApi extends BaseApi {
-  Future<String> crateApiHelloWorld();
-}
-
-class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
-  RustLibApiImpl({
-    required super.handler,
-    required super.wire,
-    required super.generalizedFrbRustBinding,
-    required super.portManager,
-  });
-
-  @override
-  Future<String> crateApiHelloWorld() {
-    return handler.executeNormal(NormalTask(
-      callFfi: (port_) {
-        final serializer = SseSerializer(generalizedFrbRustBinding);
-        pdeCallFfi(generalizedFrbRustBinding, serializer,
-            funcId: 1, port: port_);
-      },
-      codec: SseCodec(
-        decodeSuccessData: sse_decode_String,
-        decodeErrorData: null,
-      ),
-      constMeta: kCrateApiHelloWorldConstMeta,
-      argValues: [],
-      apiImpl: this,
-    ));
-  }
-
-  TaskConstMeta get kCrateApiHelloWorldConstMeta => const TaskConstMeta(
-        debugName: "hello_world",
-        argNames: [],
-      );
-
-  @protected
-  String dco_decode_String(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as String;
-  }
-
-  @protected
-  Uint8List dco_decode_list_prim_u_8_strict(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as Uint8List;
-  }
-
-  @protected
-  int dco_decode_u_8(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as int;
-  }
-
-  @protected
-  void dco_decode_unit(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return;
-  }
-
-  @protected
-  String sse_decode_String(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var inner = sse_decode_list_prim_u_8_strict(deserializer);
-    return utf8.decoder.convert(inner);
-  }
-
-  @protected
-  Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var len_ = sse_decode_i_32(deserializer);
-    return deserializer.buffer.getUint8List(len_);
-  }
-
-  @protected
-  int sse_decode_u_8(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getUint8();
-  }
-
-  @protected
-  void sse_decode_unit(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-  }
-
-  @protected
-  int sse_decode_i_32(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getInt32();
-  }
-
-  @protected
-  bool sse_decode_bool(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getUint8() != 0;
-  }
-
-  @protected
-  void sse_encode_String(String self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_list_prim_u_8_strict(utf8.encoder.convert(self), serializer);
-  }
-
-  @protected
-  void sse_encode_list_prim_u_8_strict(
-      Uint8List self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_i_32(self.length, serializer);
-    serializer.buffer.putUint8List(self);
-  }
-
-  @protected
-  void sse_encode_u_8(int self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putUint8(self);
-  }
-
-  @protected
-  void sse_encode_unit(void self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-  }
-
-  @protected
-  void sse_encode_i_32(int self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putInt32(self);
-  }
-
-  @protected
-  void sse_encode_bool(bool self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putUint8(self ? 1 : 0);
-  }
-}
+
+                /// Main entrypoint of the Rust API
+                class RustLib extends BaseEntrypoint<RustLibApi, RustLibApiImpl, RustLibWire> {
+                  @internal
+                  static final instance = RustLib._();
+
+                  RustLib._();
+
+                  /// Initialize flutter_rust_bridge
+                  static Future<void> init({
+                    RustLibApi? api,
+                    BaseHandler? handler,
+                    ExternalLibrary? externalLibrary,
+                  }) async {
+                    await instance.initImpl(
+                      api: api,
+                      handler: handler,
+                      externalLibrary: externalLibrary,
+                    );
+                  }
+
+                  /// Initialize flutter_rust_bridge in mock mode.
+                  /// No libraries for FFI are loaded.
+                  static void initMock({
+                    required RustLibApi api,
+                  }) {
+                    instance.initMockImpl(
+                      api: api,
+                    );
+                  }
+
+                  /// Dispose flutter_rust_bridge
+                  ///
+                  /// The call to this function is optional, since flutter_rust_bridge (and everything else)
+                  /// is automatically disposed when the app stops.
+                  static void dispose() => instance.disposeImpl();
+
+                  @override
+                  ApiImplConstructor<RustLibApiImpl, RustLibWire> get apiImplConstructor => RustLibApiImpl.new;
+
+                  @override
+                  WireConstructor<RustLibWire> get wireConstructor => RustLibWire.fromExternalLibrary;
+
+                  @override
+                  Future<void> executeRustInitializers() async {
+                    await api.crateApiInitApp();
+
+                  }
+
+                  @override
+                  ExternalLibraryLoaderConfig get defaultExternalLibraryLoaderConfig => kDefaultExternalLibraryLoaderConfig;
+
+                  @override
+                  String get codegenVersion => '2.7.0';
+
+                  @override
+                  int get rustContentHash => 1420997454;
+
+                  static const kDefaultExternalLibraryLoaderConfig = ExternalLibraryLoaderConfig(
+                    stem: 'flutter_gstreamer',
+                    ioDirectory: 'rust/target/release/',
+                    webPrefix: 'pkg/',
+                  );
+                }
+                
+
+                abstract class RustLibApi extends BaseApi {
+                  Future<List<AudioDevice>> crateApiAudioDevices();
+
+SessionId crateApiCreateSession({String? configJson });
+
+Future<void> crateApiDispose({required SessionId session });
+
+Future<void> crateApiInitApp();
+
+Future<void> crateApiOpen({required SessionId session , required String uri });
+
+Future<void> crateApiPause({required SessionId session });
+
+Future<void> crateApiPlay({required SessionId session });
+
+Future<void> crateApiSaveClip({required SessionId session , required Duration pre , required Duration post , required String path });
+
+Future<void> crateApiSelectAudioTrack({required SessionId session , required String id });
+
+Future<void> crateApiSelectVideoTrack({required SessionId session , required String id });
+
+Future<void> crateApiSetAudioDevice({required SessionId session , required AudioDevice device });
+
+Future<void> crateApiSetMute({required SessionId session , required bool muted });
+
+Future<void> crateApiSetRenderSize({required SessionId session , required int width , required int height });
+
+Future<void> crateApiSetStreamVariants({required SessionId session , required List<StreamVariant> variants });
+
+Future<void> crateApiSetTexture({required SessionId session , required BigInt texturePtr , required int width , required int height });
+
+Future<void> crateApiSetVolume({required SessionId session , required double volume });
+
+Future<Uint8List> crateApiSnapshot({required SessionId session , required SnapshotFormat format , required int quality });
+
+Future<void> crateApiStartRecording({required SessionId session , required String configJson });
+
+Stream<PlayerState> crateApiStateStream({required SessionId session });
+
+Stream<StreamStats> crateApiStatsStream({required SessionId session });
+
+Future<void> crateApiStop({required SessionId session });
+
+Future<void> crateApiStopRecording({required SessionId session });
+
+RustArcIncrementStrongCountFnType get rust_arc_increment_strong_count_SessionId;
+
+RustArcDecrementStrongCountFnType get rust_arc_decrement_strong_count_SessionId;
+
+CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_SessionIdPtr;
+
+
+                }
+                
+
+                class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
+                  RustLibApiImpl({
+                    required super.handler,
+                    required super.wire,
+                    required super.generalizedFrbRustBinding,
+                    required super.portManager,
+                  });
+
+                  @override Future<List<AudioDevice>> crateApiAudioDevices()  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 1, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_audio_device,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiAudioDevicesConstMeta,
+            argValues: [],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiAudioDevicesConstMeta => const TaskConstMeta(
+            debugName: "audio_devices",
+            argNames: [],
+        );
+        
+
+@override SessionId crateApiCreateSession({String? configJson })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_opt_String(configJson, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 2)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiCreateSessionConstMeta,
+            argValues: [configJson],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiCreateSessionConstMeta => const TaskConstMeta(
+            debugName: "create_session",
+            argNames: ["configJson"],
+        );
+        
+
+@override Future<void> crateApiDispose({required SessionId session })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 3, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiDisposeConstMeta,
+            argValues: [session],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiDisposeConstMeta => const TaskConstMeta(
+            debugName: "dispose",
+            argNames: ["session"],
+        );
+        
+
+@override Future<void> crateApiInitApp()  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 4, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiInitAppConstMeta,
+            argValues: [],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiInitAppConstMeta => const TaskConstMeta(
+            debugName: "init_app",
+            argNames: [],
+        );
+        
+
+@override Future<void> crateApiOpen({required SessionId session , required String uri })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_String(uri, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 5, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiOpenConstMeta,
+            argValues: [session, uri],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiOpenConstMeta => const TaskConstMeta(
+            debugName: "open",
+            argNames: ["session", "uri"],
+        );
+        
+
+@override Future<void> crateApiPause({required SessionId session })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 6, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiPauseConstMeta,
+            argValues: [session],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiPauseConstMeta => const TaskConstMeta(
+            debugName: "pause",
+            argNames: ["session"],
+        );
+        
+
+@override Future<void> crateApiPlay({required SessionId session })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 7, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiPlayConstMeta,
+            argValues: [session],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiPlayConstMeta => const TaskConstMeta(
+            debugName: "play",
+            argNames: ["session"],
+        );
+        
+
+@override Future<void> crateApiSaveClip({required SessionId session , required Duration pre , required Duration post , required String path })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_Chrono_Duration(pre, serializer);
+sse_encode_Chrono_Duration(post, serializer);
+sse_encode_String(path, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 8, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSaveClipConstMeta,
+            argValues: [session, pre, post, path],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSaveClipConstMeta => const TaskConstMeta(
+            debugName: "save_clip",
+            argNames: ["session", "pre", "post", "path"],
+        );
+        
+
+@override Future<void> crateApiSelectAudioTrack({required SessionId session , required String id })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_String(id, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 9, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSelectAudioTrackConstMeta,
+            argValues: [session, id],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSelectAudioTrackConstMeta => const TaskConstMeta(
+            debugName: "select_audio_track",
+            argNames: ["session", "id"],
+        );
+        
+
+@override Future<void> crateApiSelectVideoTrack({required SessionId session , required String id })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_String(id, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 10, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSelectVideoTrackConstMeta,
+            argValues: [session, id],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSelectVideoTrackConstMeta => const TaskConstMeta(
+            debugName: "select_video_track",
+            argNames: ["session", "id"],
+        );
+        
+
+@override Future<void> crateApiSetAudioDevice({required SessionId session , required AudioDevice device })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_box_autoadd_audio_device(device, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 11, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSetAudioDeviceConstMeta,
+            argValues: [session, device],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSetAudioDeviceConstMeta => const TaskConstMeta(
+            debugName: "set_audio_device",
+            argNames: ["session", "device"],
+        );
+        
+
+@override Future<void> crateApiSetMute({required SessionId session , required bool muted })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_bool(muted, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 12, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSetMuteConstMeta,
+            argValues: [session, muted],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSetMuteConstMeta => const TaskConstMeta(
+            debugName: "set_mute",
+            argNames: ["session", "muted"],
+        );
+        
+
+@override Future<void> crateApiSetRenderSize({required SessionId session , required int width , required int height })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_u_32(width, serializer);
+sse_encode_u_32(height, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 13, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSetRenderSizeConstMeta,
+            argValues: [session, width, height],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSetRenderSizeConstMeta => const TaskConstMeta(
+            debugName: "set_render_size",
+            argNames: ["session", "width", "height"],
+        );
+        
+
+@override Future<void> crateApiSetStreamVariants({required SessionId session , required List<StreamVariant> variants })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_list_stream_variant(variants, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 14, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSetStreamVariantsConstMeta,
+            argValues: [session, variants],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSetStreamVariantsConstMeta => const TaskConstMeta(
+            debugName: "set_stream_variants",
+            argNames: ["session", "variants"],
+        );
+        
+
+@override Future<void> crateApiSetTexture({required SessionId session , required BigInt texturePtr , required int width , required int height })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_usize(texturePtr, serializer);
+sse_encode_u_32(width, serializer);
+sse_encode_u_32(height, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 15, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSetTextureConstMeta,
+            argValues: [session, texturePtr, width, height],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSetTextureConstMeta => const TaskConstMeta(
+            debugName: "set_texture",
+            argNames: ["session", "texturePtr", "width", "height"],
+        );
+        
+
+@override Future<void> crateApiSetVolume({required SessionId session , required double volume })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_f_64(volume, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 16, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSetVolumeConstMeta,
+            argValues: [session, volume],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSetVolumeConstMeta => const TaskConstMeta(
+            debugName: "set_volume",
+            argNames: ["session", "volume"],
+        );
+        
+
+@override Future<Uint8List> crateApiSnapshot({required SessionId session , required SnapshotFormat format , required int quality })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_snapshot_format(format, serializer);
+sse_encode_u_8(quality, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 17, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_prim_u_8_strict,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSnapshotConstMeta,
+            argValues: [session, format, quality],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSnapshotConstMeta => const TaskConstMeta(
+            debugName: "snapshot",
+            argNames: ["session", "format", "quality"],
+        );
+        
+
+@override Future<void> crateApiStartRecording({required SessionId session , required String configJson })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_String(configJson, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 18, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiStartRecordingConstMeta,
+            argValues: [session, configJson],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiStartRecordingConstMeta => const TaskConstMeta(
+            debugName: "start_recording",
+            argNames: ["session", "configJson"],
+        );
+        
+
+@override Stream<PlayerState> crateApiStateStream({required SessionId session })  { 
+            final sink = RustStreamSink<PlayerState>();
+            unawaited(handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_StreamSink_player_state_Sse(sink, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 19, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiStateStreamConstMeta,
+            argValues: [session, sink],
+            apiImpl: this,
+        )));
+            return sink.stream;
+             }
+
+
+        TaskConstMeta get kCrateApiStateStreamConstMeta => const TaskConstMeta(
+            debugName: "state_stream",
+            argNames: ["session", "sink"],
+        );
+        
+
+@override Stream<StreamStats> crateApiStatsStream({required SessionId session })  { 
+            final sink = RustStreamSink<StreamStats>();
+            unawaited(handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+sse_encode_StreamSink_stream_stats_Sse(sink, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 20, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiStatsStreamConstMeta,
+            argValues: [session, sink],
+            apiImpl: this,
+        )));
+            return sink.stream;
+             }
+
+
+        TaskConstMeta get kCrateApiStatsStreamConstMeta => const TaskConstMeta(
+            debugName: "stats_stream",
+            argNames: ["session", "sink"],
+        );
+        
+
+@override Future<void> crateApiStop({required SessionId session })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 21, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiStopConstMeta,
+            argValues: [session],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiStopConstMeta => const TaskConstMeta(
+            debugName: "stop",
+            argNames: ["session"],
+        );
+        
+
+@override Future<void> crateApiStopRecording({required SessionId session })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(session, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 22, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiStopRecordingConstMeta,
+            argValues: [session],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiStopRecordingConstMeta => const TaskConstMeta(
+            debugName: "stop_recording",
+            argNames: ["session"],
+        );
+        
+
+RustArcIncrementStrongCountFnType get rust_arc_increment_strong_count_SessionId => wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId;
+
+RustArcDecrementStrongCountFnType get rust_arc_decrement_strong_count_SessionId => wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId;
+
+
+
+                  @protected AnyhowException dco_decode_AnyhowException(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return AnyhowException(raw as String); }
+
+@protected SessionId dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return SessionIdImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected SessionId dco_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return SessionIdImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected Duration dco_decode_Chrono_Duration(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeDuration(dco_decode_i_64(raw).toInt()); }
+
+@protected SessionId dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return SessionIdImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected RustStreamSink<PlayerState> dco_decode_StreamSink_player_state_Sse(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+throw UnimplementedError(); }
+
+@protected RustStreamSink<StreamStats> dco_decode_StreamSink_stream_stats_Sse(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+throw UnimplementedError(); }
+
+@protected String dco_decode_String(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as String; }
+
+@protected AudioDevice dco_decode_audio_device(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+                return AudioDevice(name: dco_decode_String(arr[0]),
+description: dco_decode_String(arr[1]),); }
+
+@protected AudioParams dco_decode_audio_params(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return AudioParams(format: dco_decode_opt_String(arr[0]),
+sampleRate: dco_decode_opt_box_autoadd_u_32(arr[1]),
+channelCount: dco_decode_opt_box_autoadd_u_32(arr[2]),); }
+
+@protected bool dco_decode_bool(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as bool; }
+
+@protected AudioDevice dco_decode_box_autoadd_audio_device(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_audio_device(raw); }
+
+@protected double dco_decode_box_autoadd_f_64(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as double; }
+
+@protected int dco_decode_box_autoadd_u_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected double dco_decode_f_64(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as double; }
+
+@protected int dco_decode_i_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected PlatformInt64 dco_decode_i_64(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeI64(raw); }
+
+@protected List<AudioDevice> dco_decode_list_audio_device(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_audio_device).toList(); }
+
+@protected List<MediaTrack> dco_decode_list_media_track(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_media_track).toList(); }
+
+@protected Uint8List dco_decode_list_prim_u_8_strict(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as Uint8List; }
+
+@protected List<StreamVariant> dco_decode_list_stream_variant(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_stream_variant).toList(); }
+
+@protected MediaTrack dco_decode_media_track(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 10) throw Exception('unexpected arr length: expect 10 but see ${arr.length}');
+                return MediaTrack(id: dco_decode_String(arr[0]),
+title: dco_decode_opt_String(arr[1]),
+language: dco_decode_opt_String(arr[2]),
+codec: dco_decode_opt_String(arr[3]),
+w: dco_decode_opt_box_autoadd_u_32(arr[4]),
+h: dco_decode_opt_box_autoadd_u_32(arr[5]),
+fps: dco_decode_opt_box_autoadd_f_64(arr[6]),
+bitrate: dco_decode_opt_box_autoadd_u_32(arr[7]),
+samplerate: dco_decode_opt_box_autoadd_u_32(arr[8]),
+channelscount: dco_decode_opt_box_autoadd_u_32(arr[9]),); }
+
+@protected String? dco_decode_opt_String(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_String(raw); }
+
+@protected double? dco_decode_opt_box_autoadd_f_64(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_f_64(raw); }
+
+@protected int? dco_decode_opt_box_autoadd_u_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_u_32(raw); }
+
+@protected PlayerState dco_decode_player_state(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 19) throw Exception('unexpected arr length: expect 19 but see ${arr.length}');
+                return PlayerState(playing: dco_decode_bool(arr[0]),
+completed: dco_decode_bool(arr[1]),
+buffering: dco_decode_bool(arr[2]),
+bufferingPercentage: dco_decode_f_64(arr[3]),
+position: dco_decode_Chrono_Duration(arr[4]),
+latency: dco_decode_Chrono_Duration(arr[5]),
+width: dco_decode_opt_box_autoadd_u_32(arr[6]),
+height: dco_decode_opt_box_autoadd_u_32(arr[7]),
+textureWidth: dco_decode_opt_box_autoadd_u_32(arr[8]),
+textureHeight: dco_decode_opt_box_autoadd_u_32(arr[9]),
+videoParams: dco_decode_video_params(arr[10]),
+audioParams: dco_decode_audio_params(arr[11]),
+framesRendered: dco_decode_u_64(arr[12]),
+framesDropped: dco_decode_u_64(arr[13]),
+volume: dco_decode_f_64(arr[14]),
+muted: dco_decode_bool(arr[15]),
+audioDevice: dco_decode_audio_device(arr[16]),
+track: dco_decode_track(arr[17]),
+tracks: dco_decode_tracks(arr[18]),); }
+
+@protected SnapshotFormat dco_decode_snapshot_format(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return SnapshotFormat.values[raw as int]; }
+
+@protected StreamStats dco_decode_stream_stats(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 8) throw Exception('unexpected arr length: expect 8 but see ${arr.length}');
+                return StreamStats(bitrate: dco_decode_u_64(arr[0]),
+fpsReceived: dco_decode_f_64(arr[1]),
+fpsRendered: dco_decode_f_64(arr[2]),
+packetsReceived: dco_decode_u_64(arr[3]),
+packetsLost: dco_decode_u_64(arr[4]),
+jitter: dco_decode_Chrono_Duration(arr[5]),
+latency: dco_decode_Chrono_Duration(arr[6]),
+framesDropped: dco_decode_u_64(arr[7]),); }
+
+@protected StreamVariant dco_decode_stream_variant(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return StreamVariant(uri: dco_decode_String(arr[0]),
+width: dco_decode_u_32(arr[1]),
+height: dco_decode_u_32(arr[2]),); }
+
+@protected Track dco_decode_track(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+                return Track(video: dco_decode_String(arr[0]),
+audio: dco_decode_String(arr[1]),); }
+
+@protected Tracks dco_decode_tracks(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return Tracks(video: dco_decode_list_media_track(arr[0]),
+audio: dco_decode_list_media_track(arr[1]),
+subtitle: dco_decode_list_media_track(arr[2]),); }
+
+@protected int dco_decode_u_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected BigInt dco_decode_u_64(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeU64(raw); }
+
+@protected int dco_decode_u_8(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected void dco_decode_unit(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return; }
+
+@protected BigInt dco_decode_usize(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeU64(raw); }
+
+@protected VideoParams dco_decode_video_params(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 9) throw Exception('unexpected arr length: expect 9 but see ${arr.length}');
+                return VideoParams(pixelformat: dco_decode_opt_String(arr[0]),
+w: dco_decode_opt_box_autoadd_u_32(arr[1]),
+h: dco_decode_opt_box_autoadd_u_32(arr[2]),
+dw: dco_decode_opt_box_autoadd_u_32(arr[3]),
+dh: dco_decode_opt_box_autoadd_u_32(arr[4]),
+aspect: dco_decode_opt_box_autoadd_f_64(arr[5]),
+par: dco_decode_opt_box_autoadd_f_64(arr[6]),
+colorimetry: dco_decode_opt_String(arr[7]),
+fps: dco_decode_opt_box_autoadd_f_64(arr[8]),); }
+
+@protected AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_String(deserializer);
+        return AnyhowException(inner); }
+
+@protected SessionId sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return SessionIdImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected SessionId sse_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return SessionIdImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected Duration sse_decode_Chrono_Duration(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_i_64(deserializer);
+        return Duration(microseconds: inner.toInt()); }
+
+@protected SessionId sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return SessionIdImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected RustStreamSink<PlayerState> sse_decode_StreamSink_player_state_Sse(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+throw UnimplementedError('Unreachable ()'); }
+
+@protected RustStreamSink<StreamStats> sse_decode_StreamSink_stream_stats_Sse(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+throw UnimplementedError('Unreachable ()'); }
+
+@protected String sse_decode_String(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_list_prim_u_8_strict(deserializer);
+        return utf8.decoder.convert(inner); }
+
+@protected AudioDevice sse_decode_audio_device(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_name = sse_decode_String(deserializer);
+var var_description = sse_decode_String(deserializer);
+return AudioDevice(name: var_name, description: var_description); }
+
+@protected AudioParams sse_decode_audio_params(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_format = sse_decode_opt_String(deserializer);
+var var_sampleRate = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_channelCount = sse_decode_opt_box_autoadd_u_32(deserializer);
+return AudioParams(format: var_format, sampleRate: var_sampleRate, channelCount: var_channelCount); }
+
+@protected bool sse_decode_bool(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getUint8() != 0; }
+
+@protected AudioDevice sse_decode_box_autoadd_audio_device(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_audio_device(deserializer)); }
+
+@protected double sse_decode_box_autoadd_f_64(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_f_64(deserializer)); }
+
+@protected int sse_decode_box_autoadd_u_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_u_32(deserializer)); }
+
+@protected double sse_decode_f_64(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getFloat64(); }
+
+@protected int sse_decode_i_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getInt32(); }
+
+@protected PlatformInt64 sse_decode_i_64(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getPlatformInt64(); }
+
+@protected List<AudioDevice> sse_decode_list_audio_device(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <AudioDevice>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_audio_device(deserializer)); }
+        return ans_;
+         }
+
+@protected List<MediaTrack> sse_decode_list_media_track(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <MediaTrack>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_media_track(deserializer)); }
+        return ans_;
+         }
+
+@protected Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var len_ = sse_decode_i_32(deserializer);
+                return deserializer.buffer.getUint8List(len_); }
+
+@protected List<StreamVariant> sse_decode_list_stream_variant(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <StreamVariant>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_stream_variant(deserializer)); }
+        return ans_;
+         }
+
+@protected MediaTrack sse_decode_media_track(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_id = sse_decode_String(deserializer);
+var var_title = sse_decode_opt_String(deserializer);
+var var_language = sse_decode_opt_String(deserializer);
+var var_codec = sse_decode_opt_String(deserializer);
+var var_w = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_h = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_fps = sse_decode_opt_box_autoadd_f_64(deserializer);
+var var_bitrate = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_samplerate = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_channelscount = sse_decode_opt_box_autoadd_u_32(deserializer);
+return MediaTrack(id: var_id, title: var_title, language: var_language, codec: var_codec, w: var_w, h: var_h, fps: var_fps, bitrate: var_bitrate, samplerate: var_samplerate, channelscount: var_channelscount); }
+
+@protected String? sse_decode_opt_String(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_String(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected double? sse_decode_opt_box_autoadd_f_64(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_f_64(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected int? sse_decode_opt_box_autoadd_u_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_u_32(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected PlayerState sse_decode_player_state(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_playing = sse_decode_bool(deserializer);
+var var_completed = sse_decode_bool(deserializer);
+var var_buffering = sse_decode_bool(deserializer);
+var var_bufferingPercentage = sse_decode_f_64(deserializer);
+var var_position = sse_decode_Chrono_Duration(deserializer);
+var var_latency = sse_decode_Chrono_Duration(deserializer);
+var var_width = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_height = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_textureWidth = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_textureHeight = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_videoParams = sse_decode_video_params(deserializer);
+var var_audioParams = sse_decode_audio_params(deserializer);
+var var_framesRendered = sse_decode_u_64(deserializer);
+var var_framesDropped = sse_decode_u_64(deserializer);
+var var_volume = sse_decode_f_64(deserializer);
+var var_muted = sse_decode_bool(deserializer);
+var var_audioDevice = sse_decode_audio_device(deserializer);
+var var_track = sse_decode_track(deserializer);
+var var_tracks = sse_decode_tracks(deserializer);
+return PlayerState(playing: var_playing, completed: var_completed, buffering: var_buffering, bufferingPercentage: var_bufferingPercentage, position: var_position, latency: var_latency, width: var_width, height: var_height, textureWidth: var_textureWidth, textureHeight: var_textureHeight, videoParams: var_videoParams, audioParams: var_audioParams, framesRendered: var_framesRendered, framesDropped: var_framesDropped, volume: var_volume, muted: var_muted, audioDevice: var_audioDevice, track: var_track, tracks: var_tracks); }
+
+@protected SnapshotFormat sse_decode_snapshot_format(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_i_32(deserializer);
+        return SnapshotFormat.values[inner]; }
+
+@protected StreamStats sse_decode_stream_stats(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_bitrate = sse_decode_u_64(deserializer);
+var var_fpsReceived = sse_decode_f_64(deserializer);
+var var_fpsRendered = sse_decode_f_64(deserializer);
+var var_packetsReceived = sse_decode_u_64(deserializer);
+var var_packetsLost = sse_decode_u_64(deserializer);
+var var_jitter = sse_decode_Chrono_Duration(deserializer);
+var var_latency = sse_decode_Chrono_Duration(deserializer);
+var var_framesDropped = sse_decode_u_64(deserializer);
+return StreamStats(bitrate: var_bitrate, fpsReceived: var_fpsReceived, fpsRendered: var_fpsRendered, packetsReceived: var_packetsReceived, packetsLost: var_packetsLost, jitter: var_jitter, latency: var_latency, framesDropped: var_framesDropped); }
+
+@protected StreamVariant sse_decode_stream_variant(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_uri = sse_decode_String(deserializer);
+var var_width = sse_decode_u_32(deserializer);
+var var_height = sse_decode_u_32(deserializer);
+return StreamVariant(uri: var_uri, width: var_width, height: var_height); }
+
+@protected Track sse_decode_track(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_video = sse_decode_String(deserializer);
+var var_audio = sse_decode_String(deserializer);
+return Track(video: var_video, audio: var_audio); }
+
+@protected Tracks sse_decode_tracks(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_video = sse_decode_list_media_track(deserializer);
+var var_audio = sse_decode_list_media_track(deserializer);
+var var_subtitle = sse_decode_list_media_track(deserializer);
+return Tracks(video: var_video, audio: var_audio, subtitle: var_subtitle); }
+
+@protected int sse_decode_u_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getUint32(); }
+
+@protected BigInt sse_decode_u_64(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getBigUint64(); }
+
+@protected int sse_decode_u_8(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getUint8(); }
+
+@protected void sse_decode_unit(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+ }
+
+@protected BigInt sse_decode_usize(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getBigUint64(); }
+
+@protected VideoParams sse_decode_video_params(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_pixelformat = sse_decode_opt_String(deserializer);
+var var_w = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_h = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_dw = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_dh = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_aspect = sse_decode_opt_box_autoadd_f_64(deserializer);
+var var_par = sse_decode_opt_box_autoadd_f_64(deserializer);
+var var_colorimetry = sse_decode_opt_String(deserializer);
+var var_fps = sse_decode_opt_box_autoadd_f_64(deserializer);
+return VideoParams(pixelformat: var_pixelformat, w: var_w, h: var_h, dw: var_dw, dh: var_dh, aspect: var_aspect, par: var_par, colorimetry: var_colorimetry, fps: var_fps); }
+
+@protected void sse_encode_AnyhowException(AnyhowException self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.message, serializer); }
+
+@protected void sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(SessionId self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as SessionIdImpl).frbInternalSseEncode(move: true), serializer); }
+
+@protected void sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(SessionId self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as SessionIdImpl).frbInternalSseEncode(move: false), serializer); }
+
+@protected void sse_encode_Chrono_Duration(Duration self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_64(PlatformInt64Util.from(self.inMicroseconds), serializer); }
+
+@protected void sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerSessionId(SessionId self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as SessionIdImpl).frbInternalSseEncode(move: null), serializer); }
+
+@protected void sse_encode_StreamSink_player_state_Sse(RustStreamSink<PlayerState> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.setupAndSerialize(codec: SseCodec(
+            decodeSuccessData: sse_decode_player_state,
+            decodeErrorData: sse_decode_AnyhowException,
+        )), serializer); }
+
+@protected void sse_encode_StreamSink_stream_stats_Sse(RustStreamSink<StreamStats> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.setupAndSerialize(codec: SseCodec(
+            decodeSuccessData: sse_decode_stream_stats,
+            decodeErrorData: sse_decode_AnyhowException,
+        )), serializer); }
+
+@protected void sse_encode_String(String self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_list_prim_u_8_strict(utf8.encoder.convert(self), serializer); }
+
+@protected void sse_encode_audio_device(AudioDevice self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.name, serializer);
+sse_encode_String(self.description, serializer);
+ }
+
+@protected void sse_encode_audio_params(AudioParams self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_opt_String(self.format, serializer);
+sse_encode_opt_box_autoadd_u_32(self.sampleRate, serializer);
+sse_encode_opt_box_autoadd_u_32(self.channelCount, serializer);
+ }
+
+@protected void sse_encode_bool(bool self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putUint8(self ? 1 : 0); }
+
+@protected void sse_encode_box_autoadd_audio_device(AudioDevice self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_audio_device(self, serializer); }
+
+@protected void sse_encode_box_autoadd_f_64(double self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_f_64(self, serializer); }
+
+@protected void sse_encode_box_autoadd_u_32(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self, serializer); }
+
+@protected void sse_encode_f_64(double self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putFloat64(self); }
+
+@protected void sse_encode_i_32(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putInt32(self); }
+
+@protected void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putPlatformInt64(self); }
+
+@protected void sse_encode_list_audio_device(List<AudioDevice> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_audio_device(item, serializer); } }
+
+@protected void sse_encode_list_media_track(List<MediaTrack> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_media_track(item, serializer); } }
+
+@protected void sse_encode_list_prim_u_8_strict(Uint8List self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+                    serializer.buffer.putUint8List(self); }
+
+@protected void sse_encode_list_stream_variant(List<StreamVariant> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_stream_variant(item, serializer); } }
+
+@protected void sse_encode_media_track(MediaTrack self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.id, serializer);
+sse_encode_opt_String(self.title, serializer);
+sse_encode_opt_String(self.language, serializer);
+sse_encode_opt_String(self.codec, serializer);
+sse_encode_opt_box_autoadd_u_32(self.w, serializer);
+sse_encode_opt_box_autoadd_u_32(self.h, serializer);
+sse_encode_opt_box_autoadd_f_64(self.fps, serializer);
+sse_encode_opt_box_autoadd_u_32(self.bitrate, serializer);
+sse_encode_opt_box_autoadd_u_32(self.samplerate, serializer);
+sse_encode_opt_box_autoadd_u_32(self.channelscount, serializer);
+ }
+
+@protected void sse_encode_opt_String(String? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_String(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_f_64(double? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_f_64(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_u_32(int? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_u_32(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_player_state(PlayerState self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_bool(self.playing, serializer);
+sse_encode_bool(self.completed, serializer);
+sse_encode_bool(self.buffering, serializer);
+sse_encode_f_64(self.bufferingPercentage, serializer);
+sse_encode_Chrono_Duration(self.position, serializer);
+sse_encode_Chrono_Duration(self.latency, serializer);
+sse_encode_opt_box_autoadd_u_32(self.width, serializer);
+sse_encode_opt_box_autoadd_u_32(self.height, serializer);
+sse_encode_opt_box_autoadd_u_32(self.textureWidth, serializer);
+sse_encode_opt_box_autoadd_u_32(self.textureHeight, serializer);
+sse_encode_video_params(self.videoParams, serializer);
+sse_encode_audio_params(self.audioParams, serializer);
+sse_encode_u_64(self.framesRendered, serializer);
+sse_encode_u_64(self.framesDropped, serializer);
+sse_encode_f_64(self.volume, serializer);
+sse_encode_bool(self.muted, serializer);
+sse_encode_audio_device(self.audioDevice, serializer);
+sse_encode_track(self.track, serializer);
+sse_encode_tracks(self.tracks, serializer);
+ }
+
+@protected void sse_encode_snapshot_format(SnapshotFormat self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.index, serializer); }
+
+@protected void sse_encode_stream_stats(StreamStats self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_64(self.bitrate, serializer);
+sse_encode_f_64(self.fpsReceived, serializer);
+sse_encode_f_64(self.fpsRendered, serializer);
+sse_encode_u_64(self.packetsReceived, serializer);
+sse_encode_u_64(self.packetsLost, serializer);
+sse_encode_Chrono_Duration(self.jitter, serializer);
+sse_encode_Chrono_Duration(self.latency, serializer);
+sse_encode_u_64(self.framesDropped, serializer);
+ }
+
+@protected void sse_encode_stream_variant(StreamVariant self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.uri, serializer);
+sse_encode_u_32(self.width, serializer);
+sse_encode_u_32(self.height, serializer);
+ }
+
+@protected void sse_encode_track(Track self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.video, serializer);
+sse_encode_String(self.audio, serializer);
+ }
+
+@protected void sse_encode_tracks(Tracks self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_list_media_track(self.video, serializer);
+sse_encode_list_media_track(self.audio, serializer);
+sse_encode_list_media_track(self.subtitle, serializer);
+ }
+
+@protected void sse_encode_u_32(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putUint32(self); }
+
+@protected void sse_encode_u_64(BigInt self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putBigUint64(self); }
+
+@protected void sse_encode_u_8(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putUint8(self); }
+
+@protected void sse_encode_unit(void self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+ }
+
+@protected void sse_encode_usize(BigInt self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putBigUint64(self); }
+
+@protected void sse_encode_video_params(VideoParams self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_opt_String(self.pixelformat, serializer);
+sse_encode_opt_box_autoadd_u_32(self.w, serializer);
+sse_encode_opt_box_autoadd_u_32(self.h, serializer);
+sse_encode_opt_box_autoadd_u_32(self.dw, serializer);
+sse_encode_opt_box_autoadd_u_32(self.dh, serializer);
+sse_encode_opt_box_autoadd_f_64(self.aspect, serializer);
+sse_encode_opt_box_autoadd_f_64(self.par, serializer);
+sse_encode_opt_String(self.colorimetry, serializer);
+sse_encode_opt_box_autoadd_f_64(self.fps, serializer);
+ }
+                }
+                
+
+            @sealed class SessionIdImpl extends RustOpaque implements SessionId {
+                // Not to be used by end users
+                SessionIdImpl.frbInternalDcoDecode(List<dynamic> wire):
+                    super.frbInternalDcoDecode(wire, _kStaticData);
+
+                // Not to be used by end users
+                SessionIdImpl.frbInternalSseDecode(BigInt ptr, int externalSizeOnNative):
+                    super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
+
+                static final _kStaticData = RustArcStaticData(
+                    rustArcIncrementStrongCount: RustLib.instance.api.rust_arc_increment_strong_count_SessionId,
+                    rustArcDecrementStrongCount: RustLib.instance.api.rust_arc_decrement_strong_count_SessionId,
+                    rustArcDecrementStrongCountPtr: RustLib.instance.api.rust_arc_decrement_strong_count_SessionIdPtr,
+                );
+
+                
+            }
\ No newline at end of file
@@ -1,5 +1,441 @@
+use std::time::Duration;
 
+use flutter_rust_bridge::frb;
+use rtsp_player_rs::{
+    audio, flutter_texture::FlutterTexture, snapshot, state, stats, tracks, variants, PlayerConfig,
+    RecordingConfig,
+};
 
-pub fn hello_world() -> String{
-    return String::from("Hello, World!");
-}
\ No newline at end of file
+use crate::core::session;
+use crate::frb_generated::StreamSink;
+
+/// Handle to a player session. Dropping it, including when the Dart isolate
+/// shuts down and its finalizers run, disposes the session.
+#[frb(opaque)]
+pub struct SessionId(u64);
+
+impl Drop for SessionId {
+    fn drop(&mut self) {
+        session::remove(self.0);
+    }
+}
+
+#[frb(init)]
+pub fn init_app() {
+    flutter_rust_bridge::setup_default_user_utils();
+}
+
+/// Creates a session. `config_json` is a serialised `PlayerConfig` preset,
+/// `None` uses the defaults.
+#[frb(sync)]
+pub fn create_session(config_json: Option<String>) -> anyhow::Result<SessionId> {
+    rtsp_player_rs::init_gst()?;
+    let config = match config_json {
+        Some(json) => serde_json::from_str::<PlayerConfig>(&json)?,
+        None => PlayerConfig::default(),
+    };
+    Ok(SessionId(session::create(config)))
+}
+
+pub fn open(session: &SessionId, uri: String) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| s.open(uri))
+}
+
+pub fn play(session: &SessionId) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| Ok(s.player()?.play()?))
+}
+
+pub fn pause(session: &SessionId) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| Ok(s.player()?.pause()?))
+}
+
+pub fn stop(session: &SessionId) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| Ok(s.player()?.stop()?))
+}
+
+pub fn dispose(session: &SessionId) {
+    session::remove(session.0);
+}
+
+/// Volume in percent, 100 leaving the audio unchanged.
+pub fn set_volume(session: &SessionId, volume: f64) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        s.player()?.set_volume(volume);
+        Ok(())
+    })
+}
+
+pub fn set_mute(session: &SessionId, muted: bool) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        s.player()?.set_mute(muted);
+        Ok(())
+    })
+}
+
+pub fn set_audio_device(session: &SessionId, device: AudioDevice) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        Ok(s.player()?.set_audio_device(device.into())?)
+    })
+}
+
+/// The last rendered frame, encoded with EXIF tags. `quality` only applies
+/// to JPEG.
+pub fn snapshot(
+    session: &SessionId,
+    format: SnapshotFormat,
+    quality: u8,
+) -> anyhow::Result<Vec<u8>> {
+    session::with_session(session.0, |s| {
+        Ok(s.player()?.snapshot(format.into(), quality)?)
+    })
+}
+
+/// Starts recording the stream. `config_json` is a serialised
+/// `RecordingConfig`.
+pub fn start_recording(session: &SessionId, config_json: String) -> anyhow::Result<()> {
+    let config = serde_json::from_str::<RecordingConfig>(&config_json)?;
+    session::with_session(session.0, |s| Ok(s.player()?.start_recording(config)?))
+}
+
+pub fn stop_recording(session: &SessionId) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        s.player()?.stop_recording();
+        Ok(())
+    })
+}
+
+/// Saves the buffered `pre` plus the following `post` of the stream to an
+/// MP4 at `path`. Needs `prebuffer` in the session's config.
+pub fn save_clip(
+    session: &SessionId,
+    pre: chrono::Duration,
+    post: chrono::Duration,
+    path: String,
+) -> anyhow::Result<()> {
+    let (pre, post) = (pre.to_std()?, post.to_std()?);
+    session::with_session(session.0, |s| {
+        Ok(s.player()?.save_clip(pre, post, &path)?)
+    })
+}
+
+/// Sets the main stream and substreams of the camera, see
+/// `set_render_size`.
+pub fn set_stream_variants(
+    session: &SessionId,
+    variants: Vec<StreamVariant>,
+) -> anyhow::Result<()> {
+    let variants = variants.into_iter().map(Into::into).collect();
+    session::with_session(session.0, |s| {
+        Ok(s.player()?.set_stream_variants(variants)?)
+    })
+}
+
+/// Reports the size the video is shown at, in physical pixels, so the
+/// smallest stream variant covering it is played.
+pub fn set_render_size(session: &SessionId, width: u32, height: u32) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        Ok(s.player()?.set_render_size(width, height)?)
+    })
+}
+
+/// Selects a video track by id: `auto`, `no` or a track id from `Tracks`.
+pub fn select_video_track(session: &SessionId, id: String) -> anyhow::Result<()> {
+    let selection = parse_track_id(&id)?;
+    session::with_session(session.0, |s| {
+        Ok(s.player()?.select_video_track(selection)?)
+    })
+}
+
+/// Selects an audio track by id, like `select_video_track`.
+pub fn select_audio_track(session: &SessionId, id: String) -> anyhow::Result<()> {
+    let selection = parse_track_id(&id)?;
+    session::with_session(session.0, |s| {
+        Ok(s.player()?.select_audio_track(selection)?)
+    })
+}
+
+fn parse_track_id(id: &str) -> anyhow::Result<tracks::TrackSelection> {
+    Ok(match id {
+        "auto" => tracks::TrackSelection::Auto,
+        "no" => tracks::TrackSelection::No,
+        index => tracks::TrackSelection::Index(index.parse()?),
+    })
+}
+
+fn track_id(selection: tracks::TrackSelection) -> String {
+    match selection {
+        tracks::TrackSelection::Auto => "auto".to_string(),
+        tracks::TrackSelection::No => "no".to_string(),
+        tracks::TrackSelection::Index(index) => index.to_string(),
+    }
+}
+
+/// The available audio outputs, starting with the automatic one.
+pub fn audio_devices() -> anyhow::Result<Vec<AudioDevice>> {
+    Ok(audio::audio_devices()?
+        .into_iter()
+        .map(AudioDevice::from)
+        .collect())
+}
+
+/// Points the session at the texture registered by the Dart side.
+pub fn set_texture(
+    session: &SessionId,
+    texture_ptr: usize,
+    width: u32,
+    height: u32,
+) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        s.set_texture(FlutterTexture::new(texture_ptr, width, height))
+    })
+}
+
+pub fn state_stream(session: &SessionId, sink: StreamSink<PlayerState>) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        s.add_state_sink(sink);
+        Ok(())
+    })
+}
+
+/// Link statistics, refreshed every second.
+pub fn stats_stream(session: &SessionId, sink: StreamSink<StreamStats>) -> anyhow::Result<()> {
+    session::with_session(session.0, |s| {
+        s.add_stats_sink(sink);
+        Ok(())
+    })
+}
+
+pub enum SnapshotFormat {
+    Jpeg,
+    Png,
+}
+
+impl From<SnapshotFormat> for snapshot::SnapshotFormat {
+    fn from(format: SnapshotFormat) -> Self {
+        match format {
+            SnapshotFormat::Jpeg => snapshot::SnapshotFormat::Jpeg,
+            SnapshotFormat::Png => snapshot::SnapshotFormat::Png,
+        }
+    }
+}
+
+/// Mirrors the Dart `PlayerState` DTO.
+pub struct PlayerState {
+    pub playing: bool,
+    pub completed: bool,
+    pub buffering: bool,
+    pub buffering_percentage: f64,
+    pub position: chrono::Duration,
+    pub latency: chrono::Duration,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Size of the texture; the widget should relayout when it changes.
+    pub texture_width: Option<u32>,
+    pub texture_height: Option<u32>,
+    pub video_params: VideoParams,
+    pub audio_params: AudioParams,
+    pub frames_rendered: u64,
+    pub frames_dropped: u64,
+    pub volume: f64,
+    pub muted: bool,
+    pub audio_device: AudioDevice,
+    pub track: Track,
+    pub tracks: Tracks,
+}
+
+pub struct VideoParams {
+    pub pixelformat: Option<String>,
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+    pub dw: Option<u32>,
+    pub dh: Option<u32>,
+    pub aspect: Option<f64>,
+    pub par: Option<f64>,
+    pub colorimetry: Option<String>,
+    pub fps: Option<f64>,
+}
+
+/// Mirrors the fields of the Dart `VideoTrack`/`AudioTrack`/`SubtitleTrack`
+/// DTOs that an SDP can fill.
+pub struct MediaTrack {
+    pub id: String,
+    pub title: Option<String>,
+    pub language: Option<String>,
+    pub codec: Option<String>,
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+    pub fps: Option<f64>,
+    pub bitrate: Option<u32>,
+    pub samplerate: Option<u32>,
+    pub channelscount: Option<u32>,
+}
+
+pub struct Tracks {
+    pub video: Vec<MediaTrack>,
+    pub audio: Vec<MediaTrack>,
+    pub subtitle: Vec<MediaTrack>,
+}
+
+/// Ids of the selected tracks.
+pub struct Track {
+    pub video: String,
+    pub audio: String,
+}
+
+pub struct AudioDevice {
+    pub name: String,
+    pub description: String,
+}
+
+pub struct StreamStats {
+    pub bitrate: u64,
+    pub fps_received: f64,
+    pub fps_rendered: f64,
+    pub packets_received: u64,
+    pub packets_lost: u64,
+    pub jitter: chrono::Duration,
+    pub latency: chrono::Duration,
+    pub frames_dropped: u64,
+}
+
+pub struct StreamVariant {
+    pub uri: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub struct AudioParams {
+    pub format: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub channel_count: Option<u32>,
+}
+
+fn to_chrono(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or_default()
+}
+
+impl From<state::PlayerState> for PlayerState {
+    fn from(state: state::PlayerState) -> Self {
+        Self {
+            playing: state.playing,
+            completed: state.completed,
+            buffering: state.buffering,
+            buffering_percentage: state.buffering_percentage,
+            position: to_chrono(state.position),
+            latency: to_chrono(state.latency),
+            width: state.width,
+            height: state.height,
+            texture_width: state.texture_width,
+            texture_height: state.texture_height,
+            video_params: state.video_params.into(),
+            audio_params: state.audio_params.into(),
+            frames_rendered: state.frames_rendered,
+            frames_dropped: state.frames_dropped,
+            volume: state.volume,
+            muted: state.muted,
+            audio_device: state.audio_device.into(),
+            track: Track {
+                video: track_id(state.track.video),
+                audio: track_id(state.track.audio),
+            },
+            tracks: state.tracks.into(),
+        }
+    }
+}
+
+impl From<state::VideoParams> for VideoParams {
+    fn from(params: state::VideoParams) -> Self {
+        Self {
+            pixelformat: params.pixelformat,
+            w: params.w,
+            h: params.h,
+            dw: params.dw,
+            dh: params.dh,
+            aspect: params.aspect,
+            par: params.par,
+            colorimetry: params.colorimetry,
+            fps: params.fps,
+        }
+    }
+}
+
+impl From<state::AudioParams> for AudioParams {
+    fn from(params: state::AudioParams) -> Self {
+        Self {
+            format: params.format,
+            sample_rate: params.sample_rate,
+            channel_count: params.channel_count,
+        }
+    }
+}
+
+impl From<audio::AudioDevice> for AudioDevice {
+    fn from(device: audio::AudioDevice) -> Self {
+        Self {
+            name: device.name,
+            description: device.description,
+        }
+    }
+}
+
+impl From<AudioDevice> for audio::AudioDevice {
+    fn from(device: AudioDevice) -> Self {
+        Self {
+            name: device.name,
+            description: device.description,
+        }
+    }
+}
+
+impl From<stats::StreamStats> for StreamStats {
+    fn from(stats: stats::StreamStats) -> Self {
+        Self {
+            bitrate: stats.bitrate,
+            fps_received: stats.fps_received,
+            fps_rendered: stats.fps_rendered,
+            packets_received: stats.packets_received,
+            packets_lost: stats.packets_lost,
+            jitter: to_chrono(stats.jitter),
+            latency: to_chrono(stats.latency),
+            frames_dropped: stats.frames_dropped,
+        }
+    }
+}
+
+impl From<StreamVariant> for variants::StreamVariant {
+    fn from(variant: StreamVariant) -> Self {
+        Self {
+            uri: variant.uri,
+            width: variant.width,
+            height: variant.height,
+        }
+    }
+}
+
+impl From<tracks::Track> for MediaTrack {
+    fn from(track: tracks::Track) -> Self {
+        Self {
+            id: track.index.to_string(),
+            title: track.title,
+            language: track.language,
+            codec: track.codec,
+            w: track.w,
+            h: track.h,
+            fps: track.fps,
+            bitrate: track.bitrate,
+            samplerate: track.samplerate,
+            channelscount: track.channels,
+        }
+    }
+}
+
+impl From<tracks::Tracks> for Tracks {
+    fn from(tracks: tracks::Tracks) -> Self {
+        let convert = |tracks: Vec<tracks::Track>| tracks.into_iter().map(Into::into).collect();
+        Self {
+            video: convert(tracks.video),
+            audio: convert(tracks.audio),
+            subtitle: convert(tracks.subtitle),
+        }
+    }
+}
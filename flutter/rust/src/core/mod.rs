@@ -1 +1,2 @@
-mod session;
\ No newline at end of file
+pub(crate) mod session;
+pub(crate) mod state;
@@ -1,16 +1,133 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
-use tokio::sync::RwLock;
+use anyhow::{anyhow, Context};
+use rtsp_player_rs::{flutter_texture::FlutterTexture, PlayerConfig, RtspPlayer};
 
+use crate::api;
+use crate::core::state::{forward_state, forward_stats};
+use crate::frb_generated::StreamSink;
 
-struct FlutterGsSession {
-    
+/// One Dart-side player: its configuration, the texture it renders to and,
+/// once opened, the `RtspPlayer` itself.
+pub(crate) struct FlutterGsSession {
+    config: PlayerConfig,
+    texture: Option<FlutterTexture>,
+    uri: Option<String>,
+    player: Option<RtspPlayer>,
+    state_sinks: Vec<StreamSink<api::PlayerState>>,
+    stats_sinks: Vec<StreamSink<api::StreamStats>>,
 }
 
+impl FlutterGsSession {
+    fn new(config: PlayerConfig) -> Self {
+        Self {
+            config,
+            texture: None,
+            uri: None,
+            player: None,
+            state_sinks: Vec::new(),
+            stats_sinks: Vec::new(),
+        }
+    }
+
+    pub(crate) fn player(&self) -> anyhow::Result<&RtspPlayer> {
+        self.player
+            .as_ref()
+            .ok_or_else(|| anyhow!("Session has not been opened"))
+    }
+
+    pub(crate) fn open(&mut self, uri: String) -> anyhow::Result<()> {
+        self.uri = Some(uri);
+        self.reopen()
+    }
+
+    /// Switches to a new texture, rebuilding the player if it was open.
+    pub(crate) fn set_texture(&mut self, texture: FlutterTexture) -> anyhow::Result<()> {
+        self.texture = Some(texture);
+        let Some(player) = &self.player else {
+            return Ok(());
+        };
+        let was_playing = player.state() == gst::State::Playing;
+        self.reopen()?;
+        if was_playing {
+            self.player()?.play()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn add_state_sink(&mut self, sink: StreamSink<api::PlayerState>) {
+        if let Some(player) = &self.player {
+            forward_state(player.watch_state(), sink.clone());
+        }
+        self.state_sinks.push(sink);
+    }
+
+    pub(crate) fn add_stats_sink(&mut self, sink: StreamSink<api::StreamStats>) {
+        if let Some(player) = &self.player {
+            forward_stats(player.watch_stats(), sink.clone());
+        }
+        self.stats_sinks.push(sink);
+    }
+
+    fn reopen(&mut self) -> anyhow::Result<()> {
+        // The old pipeline has to release the texture before a new one
+        // renders to it.
+        self.player = None;
+        let uri = self.uri.clone().context("No URI to open")?;
+        let texture = self
+            .texture
+            .clone()
+            .context("set_texture must be called before open")?;
+        let player = RtspPlayer::new(uri, self.config.clone(), texture)?;
+        for sink in &self.state_sinks {
+            forward_state(player.watch_state(), sink.clone());
+        }
+        for sink in &self.stats_sinks {
+            forward_stats(player.watch_stats(), sink.clone());
+        }
+        self.player = Some(player);
+        Ok(())
+    }
+}
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
 
 lazy_static::lazy_static! {
-    // peer -> peer session, peer session -> ui sessions
-    static ref SESSIONS: RwLock<HashMap<String, FlutterGsSession>> = Default::default();
+    /// Each session has a lock of its own, so a slow call on one doesn't
+    /// hold up the others.
+    static ref SESSIONS: RwLock<HashMap<u64, Arc<Mutex<FlutterGsSession>>>> = Default::default();
+}
+
+pub(crate) fn create(config: PlayerConfig) -> u64 {
+    let id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+    SESSIONS
+        .write()
+        .unwrap()
+        .insert(id, Arc::new(Mutex::new(FlutterGsSession::new(config))));
+    id
 }
 
+pub(crate) fn with_session<T>(
+    id: u64,
+    f: impl FnOnce(&mut FlutterGsSession) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let session = SESSIONS
+        .read()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| anyhow!("Unknown session {}", id))?;
+    let mut session = session.lock().unwrap();
+    f(&mut session)
+}
 
+/// Drops the session and with it its pipeline. Unknown ids are ignored so
+/// an explicit `dispose` and the finalizer can both run.
+pub(crate) fn remove(id: u64) {
+    let session = SESSIONS.write().unwrap().remove(&id);
+    // Tear the pipeline down outside the map lock, once a call still
+    // running on the session is done with it.
+    drop(session);
+}
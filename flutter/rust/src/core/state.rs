@@ -0,0 +1,39 @@
+use rtsp_player_rs::{PlayerState, StreamStats};
+use tokio::sync::watch;
+
+use crate::api;
+use crate::frb_generated::StreamSink;
+
+/// Pushes every `PlayerState` published on `state` into `sink` until
+/// either the player or the Dart listener goes away.
+pub(crate) fn forward_state(
+    mut state: watch::Receiver<PlayerState>,
+    sink: StreamSink<api::PlayerState>,
+) {
+    flutter_rust_bridge::spawn(async move {
+        loop {
+            let current = state.borrow_and_update().clone();
+            if sink.add(current.into()).is_err() {
+                break;
+            }
+            if state.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Like `forward_state`, for the `StreamStats` published every second.
+pub(crate) fn forward_stats(
+    mut stats: watch::Receiver<StreamStats>,
+    sink: StreamSink<api::StreamStats>,
+) {
+    flutter_rust_bridge::spawn(async move {
+        while stats.changed().await.is_ok() {
+            let current = stats.borrow_and_update().clone();
+            if sink.add(current.into()).is_err() {
+                break;
+            }
+        }
+    });
+}
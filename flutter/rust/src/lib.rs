@@ -1,3 +1,3 @@
 mod frb_generated; /* AUTO INJECTED BY flutter_rust_bridge. This line may not be accurate, and you can change it according to your needs. */
 mod core;
-pub mod api;
\ No newline at end of file
+pub mod api;
@@ -0,0 +1,19 @@
+//! A GTK4 widget showing an RTSP stream through `gtk4paintablesink`.
+
+use gtk::prelude::*;
+use rtsp_player_rs::error::Result;
+
+mod sink;
+mod video_widget;
+
+pub use video_widget::RtspVideoWidget;
+
+/// Initializes GStreamer and registers `gtk4paintablesink` and the
+/// `RtspVideoWidget` type, so UI files can refer to it. Call once before
+/// creating any `RtspVideoWidget`.
+pub fn init() -> Result<()> {
+    rtsp_player_rs::init_gst()?;
+    gstgtk4::plugin_register_static()?;
+    RtspVideoWidget::static_type();
+    Ok(())
+}
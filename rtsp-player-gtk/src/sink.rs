@@ -0,0 +1,35 @@
+use gst::prelude::*;
+use gtk::gdk;
+use rtsp_player_rs::error::Result;
+use rtsp_player_rs::utils::{link_many, make_gs_element};
+
+/// Builds a `gtk4paintablesink` based video sink and returns it along with
+/// the paintable to show.
+///
+/// When GTK gave the sink a GL context the frames stay on the GPU through
+/// `glsinkbin`, otherwise they go through `videoconvert`.
+pub(crate) fn make_paintable_sink() -> Result<(gst::Element, gdk::Paintable)> {
+    let gtksink = make_gs_element("gtk4paintablesink")?;
+    // Need to set state to Ready to get a GL context
+    gtksink.set_state(gst::State::Ready)?;
+    let paintable = gtksink.property::<gdk::Paintable>("paintable");
+
+    let sink = if paintable
+        .property::<Option<gdk::GLContext>>("gl-context")
+        .is_some()
+    {
+        let glsinkbin = make_gs_element("glsinkbin")?;
+        glsinkbin.set_property("sink", &gtksink);
+        glsinkbin
+    } else {
+        let bin = gst::Bin::default();
+        let convert = make_gs_element("videoconvert")?;
+        bin.add_many([&convert, &gtksink])?;
+        link_many(&[&convert, &gtksink])?;
+        bin.add_pad(&gst::GhostPad::with_target(
+            &convert.static_pad("sink").unwrap(),
+        )?)?;
+        bin.upcast()
+    };
+    Ok((sink, paintable))
+}
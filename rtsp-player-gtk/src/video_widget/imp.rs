@@ -0,0 +1,94 @@
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use rtsp_player_rs::{PlayerConfig, RtspPlayer, StreamVariant};
+
+#[derive(glib::Properties)]
+#[properties(wrapper_type = super::RtspVideoWidget)]
+pub struct RtspVideoWidget {
+    /// Stream to show. Changing it rebuilds the player.
+    #[property(get, set = Self::set_uri, nullable)]
+    uri: RefCell<Option<String>>,
+    /// State of the pipeline, `Null` while nothing is open.
+    #[property(get, builder(gst::State::Null))]
+    pub(super) state: Cell<gst::State>,
+    /// Jitterbuffer latency in milliseconds. Changing it rebuilds the player.
+    #[property(get = Self::latency, set = Self::set_latency, maximum = 60_000)]
+    latency: PhantomData<u32>,
+    pub(super) config: RefCell<PlayerConfig>,
+    pub(super) variants: RefCell<Vec<StreamVariant>>,
+    pub(super) picture: gtk::Picture,
+    pub(super) player: RefCell<Option<RtspPlayer>>,
+}
+
+impl Default for RtspVideoWidget {
+    fn default() -> Self {
+        Self {
+            uri: RefCell::new(None),
+            state: Cell::new(gst::State::Null),
+            latency: PhantomData,
+            config: RefCell::new(PlayerConfig::default()),
+            variants: RefCell::new(Vec::new()),
+            picture: gtk::Picture::new(),
+            player: RefCell::new(None),
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for RtspVideoWidget {
+    const NAME: &'static str = "RtspVideoWidget";
+    type Type = super::RtspVideoWidget;
+    type ParentType = gtk::Widget;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.set_layout_manager_type::<gtk::BinLayout>();
+    }
+}
+
+#[glib::derived_properties]
+impl ObjectImpl for RtspVideoWidget {
+    fn constructed(&self) {
+        self.parent_constructed();
+        self.picture.set_parent(&*self.obj());
+    }
+
+    fn dispose(&self) {
+        self.player.replace(None);
+        self.picture.unparent();
+    }
+}
+
+impl WidgetImpl for RtspVideoWidget {
+    fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+        self.parent_size_allocate(width, height, baseline);
+        self.obj().update_render_size();
+    }
+}
+
+impl RtspVideoWidget {
+    fn set_uri(&self, uri: Option<String>) {
+        if *self.uri.borrow() == uri {
+            return;
+        }
+        self.uri.replace(uri);
+        self.obj().rebuild();
+    }
+
+    fn latency(&self) -> u32 {
+        self.config.borrow().latency.as_millis() as u32
+    }
+
+    fn set_latency(&self, latency: u32) {
+        let latency = Duration::from_millis(latency as u64);
+        if self.config.borrow().latency == latency {
+            return;
+        }
+        self.config.borrow_mut().latency = latency;
+        self.obj().rebuild();
+    }
+}
@@ -0,0 +1,152 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use log::warn;
+use rtsp_player_rs::error::Result;
+use rtsp_player_rs::{PlayerConfig, PlayerEvent, RtspPlayer, StreamVariant};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::sink::make_paintable_sink;
+
+mod imp;
+
+glib::wrapper! {
+    /// Shows an RTSP stream. Set `uri` and call `play`.
+    pub struct RtspVideoWidget(ObjectSubclass<imp::RtspVideoWidget>)
+    @extends gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl RtspVideoWidget {
+    pub fn new(config: PlayerConfig) -> Self {
+        let widget: Self = glib::Object::builder().build();
+        widget.set_config(config);
+        widget
+    }
+
+    pub fn config(&self) -> PlayerConfig {
+        self.imp().config.borrow().clone()
+    }
+
+    /// Replaces the configuration, rebuilding the player if a stream is
+    /// open.
+    pub fn set_config(&self, config: PlayerConfig) {
+        let latency_changed = self.imp().config.borrow().latency != config.latency;
+        self.imp().config.replace(config);
+        if latency_changed {
+            self.notify_latency();
+        }
+        self.rebuild();
+    }
+
+    /// Sets the main stream and substreams of the camera. The widget plays
+    /// the smallest one covering its size, switching as it is resized.
+    pub fn set_stream_variants(&self, variants: Vec<StreamVariant>) -> Result<()> {
+        self.imp().variants.replace(variants.clone());
+        self.with_player(|player| player.set_stream_variants(variants))?;
+        self.update_render_size();
+        Ok(())
+    }
+
+    pub fn play(&self) -> Result<()> {
+        self.with_player(RtspPlayer::play)
+    }
+
+    pub fn pause(&self) -> Result<()> {
+        self.with_player(RtspPlayer::pause)
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        self.with_player(RtspPlayer::stop)
+    }
+
+    fn with_player(&self, f: impl FnOnce(&RtspPlayer) -> Result<()>) -> Result<()> {
+        match self.imp().player.borrow().as_ref() {
+            Some(player) => f(player),
+            None => Ok(()),
+        }
+    }
+
+    /// Drops the current player and, when a URI is set, builds a new one in
+    /// the state the old one was in.
+    fn rebuild(&self) {
+        let imp = self.imp();
+        let previous_state = imp
+            .player
+            .take()
+            .map(|player| player.state())
+            .unwrap_or(gst::State::Null);
+        self.set_pipeline_state(gst::State::Null);
+
+        let Some(uri) = self.uri() else {
+            imp.picture.set_paintable(None::<&gtk::gdk::Paintable>);
+            return;
+        };
+        let player = make_paintable_sink().and_then(|(sink, paintable)| {
+            imp.picture.set_paintable(Some(&paintable));
+            RtspPlayer::with_video_sink(uri, self.config(), sink)
+        });
+        let player = match player {
+            Ok(player) => player,
+            Err(err) => {
+                warn!("Failed to build the RTSP player: {}", err);
+                return;
+            }
+        };
+        self.watch_events(&player);
+        let variants = imp.variants.borrow().clone();
+        if let Err(err) = player.set_stream_variants(variants) {
+            warn!("Failed to set the stream variants: {}", err);
+        }
+        let restored = match previous_state {
+            gst::State::Playing => player.play(),
+            gst::State::Paused => player.pause(),
+            _ => Ok(()),
+        };
+        if let Err(err) = restored {
+            warn!("Failed to restore the player state: {}", err);
+        }
+        imp.player.replace(Some(player));
+        self.update_render_size();
+    }
+
+    /// Lets the player pick the stream variant for the allocated size.
+    pub(super) fn update_render_size(&self) {
+        let scale = self.scale_factor().max(1);
+        let (width, height) = (self.width() * scale, self.height() * scale);
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        if let Err(err) =
+            self.with_player(|player| player.set_render_size(width as u32, height as u32))
+        {
+            warn!("Failed to switch stream variant: {}", err);
+        }
+    }
+
+    /// Mirrors the player's state changes into the `state` property.
+    fn watch_events(&self, player: &RtspPlayer) {
+        let mut events = player.subscribe();
+        let widget = self.downgrade();
+        glib::spawn_future_local(async move {
+            loop {
+                match events.recv().await {
+                    Ok(PlayerEvent::StateChanged(state)) => {
+                        let Some(widget) = widget.upgrade() else {
+                            break;
+                        };
+                        widget.set_pipeline_state(state);
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => (),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    fn set_pipeline_state(&self, state: gst::State) {
+        if self.imp().state.replace(state) != state {
+            self.notify_state();
+        }
+    }
+}
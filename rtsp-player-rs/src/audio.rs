@@ -0,0 +1,100 @@
+use gst::prelude::*;
+use log::warn;
+
+use crate::error::Result;
+use crate::utils::make_gs_element;
+
+/// An audio output, mirrors the Dart `AudioDevice` DTO.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioDevice {
+    /// Identifies the device, `"auto"` for the default output.
+    pub name: String,
+    pub description: String,
+}
+
+impl AudioDevice {
+    /// Lets `autoaudiosink` pick the output.
+    pub fn auto() -> Self {
+        Self {
+            name: "auto".to_string(),
+            description: String::new(),
+        }
+    }
+
+    pub fn is_auto(&self) -> bool {
+        self.name == "auto"
+    }
+}
+
+impl Default for AudioDevice {
+    fn default() -> Self {
+        Self::auto()
+    }
+}
+
+fn monitor_audio_sinks() -> Result<Vec<gst::Device>> {
+    let monitor = gst::DeviceMonitor::new();
+    monitor.add_filter(Some("Audio/Sink"), None);
+    monitor.start()?;
+    let devices = monitor.devices().into_iter().collect();
+    monitor.stop();
+    Ok(devices)
+}
+
+/// Device properties identifying an output across restarts, in order of
+/// preference. Display names aren't unique, two identical headsets share
+/// one.
+const DEVICE_ID_PROPERTIES: &[&str] = &["object.path", "device.id", "device.name", "device.strid"];
+
+fn to_audio_device(device: &gst::Device) -> AudioDevice {
+    let display_name = device.display_name().to_string();
+    let properties = device.properties();
+    let property = |name: &str| {
+        properties
+            .as_ref()
+            .and_then(|props| props.get::<String>(name).ok())
+    };
+    let name = DEVICE_ID_PROPERTIES
+        .iter()
+        .find_map(|&name| property(name))
+        .unwrap_or_else(|| display_name.clone());
+    let description = property("device.description").unwrap_or(display_name);
+    AudioDevice { name, description }
+}
+
+/// The available audio outputs, starting with `AudioDevice::auto`.
+pub fn audio_devices() -> Result<Vec<AudioDevice>> {
+    let mut devices = vec![AudioDevice::auto()];
+    devices.extend(monitor_audio_sinks()?.iter().map(to_audio_device));
+    Ok(devices)
+}
+
+/// Looks up the output behind `device`. `None` stands for the default
+/// output, also when `device` is no longer present.
+///
+/// Probing devices is slow, so this runs when the device is picked rather
+/// than when an audio branch is built.
+pub(crate) fn resolve_audio_device(device: &AudioDevice) -> Result<Option<gst::Device>> {
+    if device.is_auto() {
+        return Ok(None);
+    }
+    let found = monitor_audio_sinks()?
+        .into_iter()
+        .find(|candidate| to_audio_device(candidate).name == device.name);
+    if found.is_none() {
+        warn!(
+            "Audio device {} not found, using the default output",
+            device.name
+        );
+    }
+    Ok(found)
+}
+
+/// Creates the sink for an output found by `resolve_audio_device`,
+/// `autoaudiosink` for the default one.
+pub(crate) fn make_audio_sink(device: Option<&gst::Device>) -> Result<gst::Element> {
+    match device {
+        Some(device) => Ok(device.create_element(None)?),
+        None => make_gs_element("autoaudiosink"),
+    }
+}
@@ -0,0 +1,301 @@
+use gst::{prelude::*, Element, ElementFactory};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PlayerError, Result};
+use crate::utils::make_gs_element;
+
+/// Video encodings we know how to depayload, parse and decode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoEncodeType {
+    H264,
+    H265,
+    Mjpeg,
+    Mpeg4,
+    Vp8,
+    Vp9,
+    Av1,
+}
+
+impl VideoEncodeType {
+    /// Maps the RTP `encoding-name` caps field onto an encoding.
+    pub fn from_encoding_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "H264" => Some(Self::H264),
+            "H265" => Some(Self::H265),
+            "JPEG" => Some(Self::Mjpeg),
+            "MP4V-ES" => Some(Self::Mpeg4),
+            "VP8" => Some(Self::Vp8),
+            "VP9" => Some(Self::Vp9),
+            "AV1" => Some(Self::Av1),
+            _ => None,
+        }
+    }
+
+    pub fn depayloader(&self) -> &'static str {
+        match self {
+            Self::H264 => "rtph264depay",
+            Self::H265 => "rtph265depay",
+            Self::Mjpeg => "rtpjpegdepay",
+            Self::Mpeg4 => "rtpmp4vdepay",
+            Self::Vp8 => "rtpvp8depay",
+            Self::Vp9 => "rtpvp9depay",
+            Self::Av1 => "rtpav1depay",
+        }
+    }
+
+    pub fn parser(&self) -> Option<&'static str> {
+        match self {
+            Self::H264 => Some("h264parse"),
+            Self::H265 => Some("h265parse"),
+            Self::Mjpeg => Some("jpegparse"),
+            Self::Mpeg4 => Some("mpeg4videoparse"),
+            Self::Vp8 => None,
+            Self::Vp9 => Some("vp9parse"),
+            Self::Av1 => Some("av1parse"),
+        }
+    }
+
+    /// Decoder factories in order of preference.
+    pub fn decoders(&self) -> &'static [&'static str] {
+        match self {
+            Self::H264 => &["avdec_h264", "openh264dec"],
+            Self::H265 => &["avdec_h265", "libde265dec"],
+            Self::Mjpeg => &["jpegdec", "avdec_mjpeg"],
+            Self::Mpeg4 => &["avdec_mpeg4"],
+            Self::Vp8 => &["vp8dec", "avdec_vp8"],
+            Self::Vp9 => &["vp9dec", "avdec_vp9"],
+            Self::Av1 => &["dav1ddec", "av1dec", "avdec_av1"],
+        }
+    }
+}
+
+/// Audio encodings we know how to depayload and decode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioEncodeType {
+    Pcma,
+    Pcmu,
+    /// AAC in RFC 3640 framing.
+    AacGeneric,
+    /// AAC in LATM framing.
+    AacLatm,
+    Opus,
+    G726,
+}
+
+impl AudioEncodeType {
+    /// Maps the RTP `encoding-name` caps field onto an encoding.
+    pub fn from_encoding_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "PCMA" => Some(Self::Pcma),
+            "PCMU" => Some(Self::Pcmu),
+            "MPEG4-GENERIC" => Some(Self::AacGeneric),
+            "MP4A-LATM" => Some(Self::AacLatm),
+            "OPUS" => Some(Self::Opus),
+            // G726-16 up to G726-40, plus the AAL2 packing.
+            name if name.starts_with("G726") || name.starts_with("AAL2-G726") => Some(Self::G726),
+            _ => None,
+        }
+    }
+
+    pub fn depayloader(&self) -> &'static str {
+        match self {
+            Self::Pcma => "rtppcmadepay",
+            Self::Pcmu => "rtppcmudepay",
+            Self::AacGeneric => "rtpmp4gdepay",
+            Self::AacLatm => "rtpmp4adepay",
+            Self::Opus => "rtpopusdepay",
+            Self::G726 => "rtpg726depay",
+        }
+    }
+
+    pub fn parser(&self) -> Option<&'static str> {
+        match self {
+            Self::AacGeneric | Self::AacLatm => Some("aacparse"),
+            _ => None,
+        }
+    }
+
+    /// Decoder factories in order of preference.
+    pub fn decoders(&self) -> &'static [&'static str] {
+        match self {
+            Self::Pcma => &["alawdec"],
+            Self::Pcmu => &["mulawdec"],
+            Self::AacGeneric | Self::AacLatm => &["avdec_aac", "fdkaacdec", "faad"],
+            Self::Opus => &["opusdec"],
+            Self::G726 => &["avdec_g726"],
+        }
+    }
+}
+
+/// How the decoding part of a video branch gets built.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecoderMode {
+    /// Pick the depayloader, parser and decoder from the pad's `encoding-name`.
+    #[default]
+    Manual,
+    /// Depayload explicitly and let `decodebin3` choose the decoder.
+    DecodeBin3,
+}
+
+/// The first structure of the caps negotiated on `pad`. Pads of `rtspsrc`
+/// come with fixed caps, but a misbehaving server shouldn't take the
+/// process down with it.
+fn caps_structure_of(pad: &gst::Pad) -> Result<gst::Structure> {
+    pad.current_caps()
+        .and_then(|caps| caps.structure(0).map(|structure| structure.to_owned()))
+        .ok_or_else(|| PlayerError::UnsupportedCodec {
+            encoding: format!("{} has no caps", pad.name()),
+        })
+}
+
+pub(crate) fn media_type_of(pad: &gst::Pad) -> Result<String> {
+    let structure = caps_structure_of(pad)?;
+    structure
+        .get::<&str>("media")
+        .map(|media| media.to_string())
+        .map_err(|_| PlayerError::UnsupportedCodec {
+            encoding: structure.to_string(),
+        })
+}
+
+pub(crate) fn encoding_name_of(pad: &gst::Pad) -> Result<String> {
+    let structure = caps_structure_of(pad)?;
+    structure
+        .get::<&str>("encoding-name")
+        .map(|name| name.to_string())
+        .map_err(|_| PlayerError::UnsupportedCodec {
+            encoding: structure.to_string(),
+        })
+}
+
+fn make_first_available(factories: &[&str]) -> Result<Element> {
+    factories
+        .iter()
+        .find(|factory| ElementFactory::find(factory).is_some())
+        .map(|factory| make_gs_element(factory))
+        .unwrap_or_else(|| {
+            Err(PlayerError::ElementMissing {
+                factory: factories.join(" | "),
+            })
+        })
+}
+
+/// Builds the depay/parse/decode elements for `encoding`, in link order.
+/// The decoder is always the last element.
+///
+/// In `DecoderMode::DecodeBin3` the last element is a `decodebin3` whose
+/// source pad only appears once it has picked a decoder, so the caller has
+/// to link it from `pad-added`. Encodings missing from `VideoEncodeType`
+/// are left to `decodebin3` entirely, depayloader included.
+pub(crate) fn make_decode_chain(encoding: &str, mode: DecoderMode) -> Result<Vec<Element>> {
+    let encode_type = match (VideoEncodeType::from_encoding_name(encoding), mode) {
+        (Some(encode_type), _) => encode_type,
+        (None, DecoderMode::DecodeBin3) => return Ok(vec![make_gs_element("decodebin3")?]),
+        (None, DecoderMode::Manual) => {
+            return Err(PlayerError::UnsupportedCodec {
+                encoding: encoding.to_string(),
+            })
+        }
+    };
+
+    let mut chain = vec![make_gs_element(encode_type.depayloader())?];
+    // Parsed even in front of decodebin3, so the stream can also be
+    // recorded as is.
+    if let Some(parser) = encode_type.parser() {
+        let parser = make_gs_element(parser)?;
+        if parser.find_property("config-interval").is_some() {
+            // Repeat SPS/PPS with every keyframe, so a recording can start
+            // at any of them.
+            parser.set_property("config-interval", -1i32);
+        }
+        chain.push(parser);
+    }
+    match mode {
+        DecoderMode::Manual => chain.push(make_first_available(encode_type.decoders())?),
+        DecoderMode::DecodeBin3 => chain.push(make_gs_element("decodebin3")?),
+    }
+    Ok(chain)
+}
+
+/// Whether the elements `make_audio_decode_chain` needs for `encoding` are
+/// installed.
+pub(crate) fn can_decode_audio(encoding: &str) -> bool {
+    let available = |factory: &str| ElementFactory::find(factory).is_some();
+    AudioEncodeType::from_encoding_name(encoding).is_some_and(|encode_type| {
+        available(encode_type.depayloader())
+            && encode_type.parser().is_none_or(available)
+            && encode_type
+                .decoders()
+                .iter()
+                .any(|decoder| available(decoder))
+    })
+}
+
+/// Builds the depay/parse/decode/convert elements for an audio pad with
+/// `encoding`, in link order.
+pub(crate) fn make_audio_decode_chain(encoding: &str) -> Result<Vec<Element>> {
+    let encode_type = AudioEncodeType::from_encoding_name(encoding).ok_or_else(|| {
+        PlayerError::UnsupportedCodec {
+            encoding: encoding.to_string(),
+        }
+    })?;
+
+    let mut chain = vec![make_gs_element(encode_type.depayloader())?];
+    if let Some(parser) = encode_type.parser() {
+        chain.push(make_gs_element(parser)?);
+    }
+    chain.push(make_first_available(encode_type.decoders())?);
+    chain.push(make_gs_element("audioconvert")?);
+    chain.push(make_gs_element("audioresample")?);
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_names_are_case_insensitive() {
+        assert_eq!(
+            VideoEncodeType::from_encoding_name("h265"),
+            Some(VideoEncodeType::H265)
+        );
+        assert_eq!(
+            VideoEncodeType::from_encoding_name("MP4V-ES"),
+            Some(VideoEncodeType::Mpeg4)
+        );
+        assert_eq!(VideoEncodeType::from_encoding_name("MP2T"), None);
+    }
+
+    #[test]
+    fn g726_bitrates_map_to_one_encoding() {
+        assert_eq!(
+            AudioEncodeType::from_encoding_name("G726-32"),
+            Some(AudioEncodeType::G726)
+        );
+        assert_eq!(
+            AudioEncodeType::from_encoding_name("AAL2-G726-40"),
+            Some(AudioEncodeType::G726)
+        );
+        assert_eq!(
+            AudioEncodeType::from_encoding_name("mpeg4-generic"),
+            Some(AudioEncodeType::AacGeneric)
+        );
+    }
+
+    #[test]
+    fn pads_without_caps_are_unsupported() {
+        gst::init().unwrap();
+        let pad = gst::Pad::builder(gst::PadDirection::Src)
+            .name("recv_rtp_src_0")
+            .build();
+        assert!(matches!(
+            media_type_of(&pad),
+            Err(PlayerError::UnsupportedCodec { .. })
+        ));
+        assert!(matches!(
+            encoding_name_of(&pad),
+            Err(PlayerError::UnsupportedCodec { .. })
+        ));
+    }
+}
@@ -0,0 +1,481 @@
+use std::time::Duration;
+
+use gst::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Credentials;
+use crate::codec::DecoderMode;
+use crate::prebuffer::PrebufferConfig;
+use crate::tls::TlsConfig;
+
+/// Options for a single `RtspPlayer`.
+///
+/// Serialisable so frontends can share presets; fields missing from a
+/// serialised preset fall back to their defaults.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerConfig {
+    /// How the video decoding chain is built once `rtspsrc` exposes a pad.
+    pub decoder: DecoderMode,
+    /// Where decoded frames end up.
+    pub video_sink: VideoSinkMode,
+    /// How frames are sized for the texture on the `VideoSinkMode::Rgba`
+    /// path.
+    pub texture_size: TextureSizeMode,
+    /// Play the stream's audio track, if it has one.
+    pub audio: bool,
+    /// Keep the last seconds of an H.264/H.265 stream in memory for
+    /// `RtspPlayer::save_clip`. `None` saves the memory.
+    pub prebuffer: Option<PrebufferConfig>,
+    /// How to recover from dropped sessions. `None` leaves the pipeline
+    /// stopped after the first connection error.
+    pub reconnect: Option<ReconnectPolicy>,
+    pub transport: TransportMode,
+    /// Jitterbuffer size.
+    pub latency: Duration,
+    /// Drop late packets instead of growing the latency.
+    pub drop_on_latency: bool,
+    pub buffer_mode: BufferMode,
+    /// How long to wait for the server on TCP before failing.
+    pub tcp_timeout: Duration,
+    /// Attempts at allocating RTP ports.
+    pub retry: u32,
+    pub do_rtcp: bool,
+    pub keep_alive: bool,
+    pub user_agent: Option<String>,
+    /// Local UDP ports to use for RTP/RTCP, as an inclusive range.
+    pub udp_port_range: Option<(u16, u16)>,
+    /// Synchronise to the server's NTP clock using RTCP sender reports.
+    pub ntp_sync: bool,
+    /// Tag frames with the camera's NTP time from RTCP sender reports, see
+    /// `RawImage::captured_at`.
+    pub frame_timestamps: bool,
+    /// Dumps RTSP messages to stdout, only useful when debugging a server.
+    pub debug: bool,
+    /// Credentials for the first connection attempt. Never serialised, so
+    /// presets can be shared without leaking secrets.
+    #[serde(skip)]
+    pub credentials: Option<Credentials>,
+    /// Certificate handling for `rtsps://` URIs.
+    pub tls: Option<TlsConfig>,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            decoder: DecoderMode::default(),
+            video_sink: VideoSinkMode::default(),
+            texture_size: TextureSizeMode::default(),
+            audio: true,
+            prebuffer: None,
+            reconnect: Some(ReconnectPolicy::default()),
+            transport: TransportMode::default(),
+            latency: Duration::from_millis(200),
+            drop_on_latency: false,
+            buffer_mode: BufferMode::default(),
+            tcp_timeout: Duration::from_secs(20),
+            retry: 20,
+            do_rtcp: true,
+            keep_alive: true,
+            user_agent: None,
+            udp_port_range: None,
+            ntp_sync: false,
+            frame_timestamps: true,
+            debug: false,
+            credentials: None,
+            tls: None,
+        }
+    }
+}
+
+impl PlayerConfig {
+    pub fn builder() -> PlayerConfigBuilder {
+        PlayerConfigBuilder::default()
+    }
+
+    /// Smallest possible delay, at the cost of stutter on lossy links.
+    pub fn low_latency() -> Self {
+        Self::builder()
+            .latency(Duration::ZERO)
+            .drop_on_latency(true)
+            .buffer_mode(BufferMode::None)
+            .build()
+    }
+
+    /// Enough buffering to absorb jitter on congested links.
+    pub fn smooth() -> Self {
+        Self::builder()
+            .latency(Duration::from_secs(2))
+            .buffer_mode(BufferMode::Buffer)
+            .build()
+    }
+
+    /// Applies the `rtspsrc` related options to `rtspsrc`.
+    pub(crate) fn apply_to_rtspsrc(&self, rtspsrc: &gst::Element) {
+        rtspsrc.set_property("latency", self.latency.as_millis() as u32);
+        rtspsrc.set_property("drop-on-latency", self.drop_on_latency);
+        rtspsrc.set_property_from_str("buffer-mode", self.buffer_mode.nick());
+        rtspsrc.set_property("tcp-timeout", self.tcp_timeout.as_micros() as u64);
+        rtspsrc.set_property("retry", self.retry);
+        rtspsrc.set_property("do-rtcp", self.do_rtcp);
+        rtspsrc.set_property("do-rtsp-keep-alive", self.keep_alive);
+        if let Some(user_agent) = &self.user_agent {
+            rtspsrc.set_property("user-agent", user_agent);
+        }
+        if let Some((min, max)) = self.udp_port_range {
+            rtspsrc.set_property("port-range", format!("{}-{}", min, max));
+        }
+        rtspsrc.set_property("ntp-sync", self.ntp_sync);
+        // Only available since GStreamer 1.22.
+        if rtspsrc
+            .find_property("add-reference-timestamp-meta")
+            .is_some()
+        {
+            rtspsrc.set_property("add-reference-timestamp-meta", self.frame_timestamps);
+        }
+        rtspsrc.set_property("debug", self.debug);
+
+        if let Some(protocols) = self.transport.protocols() {
+            rtspsrc.set_property_from_str("protocols", protocols);
+        }
+        if let Some(timeout) = self.transport.udp_timeout_us() {
+            rtspsrc.set_property("timeout", timeout);
+        }
+    }
+}
+
+/// Builder for `PlayerConfig`, starting from `PlayerConfig::default()`.
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct PlayerConfigBuilder {
+    config: PlayerConfig,
+}
+
+impl PlayerConfigBuilder {
+    pub fn decoder(mut self, decoder: DecoderMode) -> Self {
+        self.config.decoder = decoder;
+        self
+    }
+
+    pub fn video_sink(mut self, video_sink: VideoSinkMode) -> Self {
+        self.config.video_sink = video_sink;
+        self
+    }
+
+    pub fn texture_size(mut self, texture_size: TextureSizeMode) -> Self {
+        self.config.texture_size = texture_size;
+        self
+    }
+
+    pub fn audio(mut self, audio: bool) -> Self {
+        self.config.audio = audio;
+        self
+    }
+
+    pub fn prebuffer(mut self, prebuffer: Option<PrebufferConfig>) -> Self {
+        self.config.prebuffer = prebuffer;
+        self
+    }
+
+    pub fn reconnect(mut self, reconnect: Option<ReconnectPolicy>) -> Self {
+        self.config.reconnect = reconnect;
+        self
+    }
+
+    pub fn transport(mut self, transport: TransportMode) -> Self {
+        self.config.transport = transport;
+        self
+    }
+
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.config.latency = latency;
+        self
+    }
+
+    pub fn drop_on_latency(mut self, drop_on_latency: bool) -> Self {
+        self.config.drop_on_latency = drop_on_latency;
+        self
+    }
+
+    pub fn buffer_mode(mut self, buffer_mode: BufferMode) -> Self {
+        self.config.buffer_mode = buffer_mode;
+        self
+    }
+
+    pub fn tcp_timeout(mut self, tcp_timeout: Duration) -> Self {
+        self.config.tcp_timeout = tcp_timeout;
+        self
+    }
+
+    pub fn retry(mut self, retry: u32) -> Self {
+        self.config.retry = retry;
+        self
+    }
+
+    pub fn do_rtcp(mut self, do_rtcp: bool) -> Self {
+        self.config.do_rtcp = do_rtcp;
+        self
+    }
+
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.config.keep_alive = keep_alive;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn udp_port_range(mut self, min: u16, max: u16) -> Self {
+        self.config.udp_port_range = Some((min, max));
+        self
+    }
+
+    pub fn ntp_sync(mut self, ntp_sync: bool) -> Self {
+        self.config.ntp_sync = ntp_sync;
+        self
+    }
+
+    pub fn frame_timestamps(mut self, frame_timestamps: bool) -> Self {
+        self.config.frame_timestamps = frame_timestamps;
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.config.debug = debug;
+        self
+    }
+
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.config.credentials = Some(credentials);
+        self
+    }
+
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.config.tls = Some(tls);
+        self
+    }
+
+    pub fn build(self) -> PlayerConfig {
+        self.config
+    }
+}
+
+/// Sink side of the pipeline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VideoSinkMode {
+    /// Render with `glimagesink` into the texture's GL context.
+    #[default]
+    Gl,
+    /// Convert to RGBA in system memory and push frames through
+    /// `FlutterTexture::on_rgba`. Works without a shareable GL context.
+    Rgba,
+    /// Hand out GL textures shared with the application's context, see
+    /// `RtspPlayer::with_gl_render`.
+    GlTexture,
+}
+
+/// Size of the frames handed to the texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextureSizeMode {
+    /// Frames keep the stream's size and the texture follows it.
+    #[default]
+    FollowStream,
+    /// Frames are always `width`x`height`, letterboxed to keep the aspect
+    /// ratio.
+    Fixed { width: u32, height: u32 },
+    /// Frames are scaled to the largest size within `width`x`height` that
+    /// keeps the stream's display aspect ratio, with square pixels.
+    Fit { width: u32, height: u32 },
+}
+
+impl TextureSizeMode {
+    /// Size of the frames for a stream with the given display size, or
+    /// `None` when the stream's own size is kept.
+    pub fn output_size(&self, display_width: u32, display_height: u32) -> Option<(u32, u32)> {
+        match *self {
+            TextureSizeMode::FollowStream => None,
+            TextureSizeMode::Fixed { width, height } => Some((width, height)),
+            TextureSizeMode::Fit { width, height } => {
+                if display_width == 0 || display_height == 0 {
+                    return Some((width, height));
+                }
+                let scale = f64::min(
+                    width as f64 / display_width as f64,
+                    height as f64 / display_height as f64,
+                );
+                Some((
+                    ((display_width as f64 * scale).round() as u32).max(1),
+                    ((display_height as f64 * scale).round() as u32).max(1),
+                ))
+            }
+        }
+    }
+}
+
+/// Jitterbuffer mode, mapped onto `rtspsrc`'s `buffer-mode` property.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BufferMode {
+    /// Only use RTP timestamps.
+    None,
+    /// Slave receiver to sender clock.
+    Slave,
+    /// Do low/high watermark buffering.
+    Buffer,
+    /// Choose the mode depending on the stream.
+    #[default]
+    Auto,
+    /// Sender and receiver clocks are synchronised.
+    Synced,
+}
+
+impl BufferMode {
+    fn nick(&self) -> &'static str {
+        match self {
+            BufferMode::None => "none",
+            BufferMode::Slave => "slave",
+            BufferMode::Buffer => "buffer",
+            BufferMode::Auto => "auto",
+            BufferMode::Synced => "synced",
+        }
+    }
+}
+
+/// Lower transport used for the RTP streams, mapped onto `rtspsrc`'s
+/// `protocols` property.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TransportMode {
+    /// Let `rtspsrc` negotiate with the server.
+    #[default]
+    Auto,
+    Tcp,
+    Udp,
+    UdpMulticast,
+    /// RTSP and RTP tunnelled over HTTP, for sites that only let HTTP out.
+    Http,
+    /// Try UDP first and switch to interleaved TCP if no data arrives
+    /// within `timeout`.
+    UdpThenTcp {
+        timeout: Duration,
+    },
+}
+
+impl TransportMode {
+    /// Value for `rtspsrc`'s `protocols` flags, `None` keeps its default.
+    pub(crate) fn protocols(&self) -> Option<&'static str> {
+        match self {
+            TransportMode::Auto => None,
+            TransportMode::Tcp => Some("tcp"),
+            TransportMode::Udp => Some("udp"),
+            TransportMode::UdpMulticast => Some("udp-mcast"),
+            TransportMode::Http => Some("tcp+http"),
+            TransportMode::UdpThenTcp { .. } => Some("udp+udp-mcast+tcp"),
+        }
+    }
+
+    /// Value for `rtspsrc`'s `timeout` property in microseconds, after which
+    /// it retries over TCP.
+    pub(crate) fn udp_timeout_us(&self) -> Option<u64> {
+        match self {
+            TransportMode::UdpThenTcp { timeout } => Some(timeout.as_micros() as u64),
+            _ => None,
+        }
+    }
+}
+
+/// Exponential backoff used between reconnect attempts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReconnectPolicy {
+    /// Give up after this many consecutive failures; `None` retries forever.
+    pub max_attempts: Option<u32>,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Fraction of the delay, between `0.0` and `1.0`, that is randomised so
+    /// a wall of cameras does not reconnect in lockstep.
+    pub jitter: f64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the given (1-based) attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff_with(attempt, fastrand::f64())
+    }
+
+    /// `random` is expected in `[0, 1)` and spreads the delay by `jitter`
+    /// in both directions.
+    fn backoff_with(&self, attempt: u32, random: f64) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let base = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (2.0 * random - 1.0);
+        base.mul_f64(factor).min(self.max_backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip_through_json() {
+        let json = serde_json::to_string(&PlayerConfig::low_latency()).unwrap();
+        let config: PlayerConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config, PlayerConfig::low_latency());
+
+        let partial: PlayerConfig = serde_json::from_str(r#"{"ntp_sync": true}"#).unwrap();
+        assert!(partial.ntp_sync);
+        assert_eq!(partial.latency, PlayerConfig::default().latency);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let policy = ReconnectPolicy {
+            max_attempts: None,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            jitter: 0.0,
+        };
+        assert_eq!(policy.backoff_with(1, 0.5), Duration::from_secs(1));
+        assert_eq!(policy.backoff_with(2, 0.5), Duration::from_secs(2));
+        assert_eq!(policy.backoff_with(4, 0.5), Duration::from_secs(8));
+        assert_eq!(policy.backoff_with(5, 0.5), Duration::from_secs(10));
+        assert_eq!(policy.backoff_with(100, 0.5), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn backoff_jitter_stays_in_bounds() {
+        let policy = ReconnectPolicy {
+            jitter: 0.5,
+            ..ReconnectPolicy::default()
+        };
+        let base = policy.initial_backoff;
+        assert_eq!(policy.backoff_with(1, 0.0), base.mul_f64(0.5));
+        assert_eq!(policy.backoff_with(1, 0.5), base);
+    }
+
+    #[test]
+    fn fit_keeps_display_aspect_ratio() {
+        let fit = TextureSizeMode::Fit {
+            width: 1280,
+            height: 1280,
+        };
+        assert_eq!(fit.output_size(1920, 1080), Some((1280, 720)));
+        assert_eq!(fit.output_size(720, 1280), Some((720, 1280)));
+        assert_eq!(TextureSizeMode::FollowStream.output_size(1920, 1080), None);
+    }
+}
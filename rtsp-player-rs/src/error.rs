@@ -0,0 +1,235 @@
+use std::sync::{Arc, Mutex};
+
+use derive_more::{Display, Error};
+use gst::{element_error, glib, prelude::*};
+
+pub type Result<T> = std::result::Result<T, PlayerError>;
+
+/// Errors surfaced by the player.
+///
+/// Bus errors are mapped from the GStreamer `ResourceError`, `StreamError`
+/// and `CoreError` domains and gio's `TlsError` and `IOErrorEnum`, so
+/// callers can react without matching on message strings. Anything we
+/// can't classify ends up in `Pipeline`.
+#[derive(Debug, Display, Error)]
+pub enum PlayerError {
+    #[display("Failed to initialize GStreamer: {_0}")]
+    Init(glib::Error),
+    #[display("GStreamer element '{factory}' is not available")]
+    ElementMissing { factory: String },
+    #[display("Failed to link {src} to {sink}")]
+    LinkFailed { src: String, sink: String },
+    #[display("Connection to the RTSP server failed: {message}")]
+    ConnectionRefused { message: String },
+    #[display("The RTSP server rejected our credentials")]
+    Unauthorized,
+    #[display("The requested stream was not found on the server")]
+    StreamNotFound,
+    #[display("Unsupported codec: {encoding}")]
+    UnsupportedCodec { encoding: String },
+    #[display("TLS failure: {message}")]
+    Tls { message: String },
+    #[display("No usable GL context")]
+    GlContextUnavailable,
+    #[display("Timed out: {message}")]
+    Timeout { message: String },
+    #[display("Snapshot failed: {message}")]
+    Snapshot { message: String },
+    #[display("Recording failed: {message}")]
+    Recording { message: String },
+    #[display("Gave up reconnecting after {attempts} attempts")]
+    ReconnectFailed { attempts: u32 },
+    #[display("All {max} decoders of the pool are in use")]
+    DecoderBudgetExhausted { max: usize },
+    #[display("Failed to change the pipeline state")]
+    StateChange(gst::StateChangeError),
+    #[display("Received error from {src}: {message} (debug: {debug:?})")]
+    Pipeline {
+        src: String,
+        message: String,
+        debug: Option<String>,
+        source: glib::Error,
+    },
+    #[display("{_0}")]
+    Glib(glib::BoolError),
+}
+
+impl PlayerError {
+    pub(crate) fn link_failed(src: &impl GstObjectExt, sink: &impl GstObjectExt) -> Self {
+        PlayerError::LinkFailed {
+            src: src.name().to_string(),
+            sink: sink.name().to_string(),
+        }
+    }
+
+    /// Whether the stream may come back by reconnecting, as opposed to
+    /// errors that will fail the same way on every attempt.
+    pub fn is_recoverable(&self) -> bool {
+        matches!(
+            self,
+            PlayerError::ConnectionRefused { .. }
+                | PlayerError::Timeout { .. }
+                | PlayerError::Pipeline { .. }
+        )
+    }
+
+    /// Maps an error message from the pipeline bus onto a `PlayerError`.
+    pub fn from_message(msg: &gst::message::Error) -> Self {
+        if let Some(err) = msg
+            .details()
+            .filter(|details| details.name() == ERROR_DETAILS)
+            .and_then(|details| details.get::<&ErrorValue>(ERROR_FIELD).ok())
+            .and_then(|value| value.0.lock().unwrap().take())
+        {
+            return err;
+        }
+
+        let error = msg.error();
+        let message = error.message().to_string();
+        let debug = msg.debug().map(|d| d.to_string());
+
+        if error.kind::<gio::TlsError>().is_some() {
+            return PlayerError::Tls { message };
+        }
+        if error.matches(gio::IOErrorEnum::TimedOut) {
+            return PlayerError::Timeout { message };
+        }
+        if let Some(kind) = error.kind::<gst::ResourceError>() {
+            match kind {
+                gst::ResourceError::NotAuthorized => return PlayerError::Unauthorized,
+                gst::ResourceError::NotFound => return PlayerError::StreamNotFound,
+                // `rtspsrc` reports data no longer arriving, over UDP or
+                // TCP, as a read error.
+                gst::ResourceError::Read if is_from_rtspsrc(msg) => {
+                    return PlayerError::Timeout { message }
+                }
+                gst::ResourceError::OpenRead
+                | gst::ResourceError::OpenReadWrite
+                | gst::ResourceError::Read => return PlayerError::ConnectionRefused { message },
+                _ => (),
+            }
+        } else if let Some(kind) = error.kind::<gst::StreamError>() {
+            match kind {
+                gst::StreamError::CodecNotFound
+                | gst::StreamError::TypeNotFound
+                | gst::StreamError::WrongType
+                | gst::StreamError::Decode => {
+                    return PlayerError::UnsupportedCodec { encoding: message }
+                }
+                _ => (),
+            }
+        } else if let Some(kind) = error.kind::<gst::CoreError>() {
+            match kind {
+                gst::CoreError::MissingPlugin => {
+                    return PlayerError::ElementMissing { factory: message }
+                }
+                gst::CoreError::Negotiation => {
+                    let src = msg.src().map(|s| s.name().to_string()).unwrap_or_default();
+                    return PlayerError::LinkFailed {
+                        src,
+                        sink: String::new(),
+                    };
+                }
+                _ => (),
+            }
+        }
+
+        PlayerError::Pipeline {
+            src: msg
+                .src()
+                .map(|s| String::from(s.path_string()))
+                .unwrap_or_else(|| String::from("None")),
+            message,
+            debug,
+            source: error,
+        }
+    }
+}
+
+impl From<gst::StateChangeError> for PlayerError {
+    fn from(err: gst::StateChangeError) -> Self {
+        PlayerError::StateChange(err)
+    }
+}
+
+impl From<glib::BoolError> for PlayerError {
+    fn from(err: glib::BoolError) -> Self {
+        PlayerError::Glib(err)
+    }
+}
+
+/// Whether `msg` was posted by an `rtspsrc` or one of the elements inside
+/// it.
+fn is_from_rtspsrc(msg: &gst::message::Error) -> bool {
+    let mut object = msg.src().cloned();
+    while let Some(current) = object {
+        let is_rtspsrc = current
+            .downcast_ref::<gst::Element>()
+            .and_then(|element| element.factory())
+            .is_some_and(|factory| factory.name() == "rtspsrc");
+        if is_rtspsrc {
+            return true;
+        }
+        object = current.parent();
+    }
+    false
+}
+
+const ERROR_DETAILS: &str = "error-details";
+const ERROR_FIELD: &str = "error";
+
+/// Carries a `PlayerError` raised inside a streaming-thread callback through
+/// the bus to `PlayerError::from_message`.
+#[derive(Clone, Debug, glib::Boxed)]
+#[boxed_type(name = "RtspPlayerErrorValue")]
+struct ErrorValue(Arc<Mutex<Option<PlayerError>>>);
+
+/// Posts `err` as an element error on `element` so it reaches the bus
+/// with its variant intact.
+pub(crate) fn post_error(element: &gst::Element, err: PlayerError) {
+    let text = err.to_string();
+    element_error!(
+        element,
+        gst::LibraryError::Failed,
+        ("{}", text),
+        details: gst::Structure::builder(ERROR_DETAILS)
+            .field(ERROR_FIELD, ErrorValue(Arc::new(Mutex::new(Some(err)))))
+            .build()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(msg: gst::Message) -> PlayerError {
+        let gst::MessageView::Error(err) = msg.view() else {
+            unreachable!()
+        };
+        PlayerError::from_message(err)
+    }
+
+    #[test]
+    fn classifies_by_error_domain() {
+        gst::init().unwrap();
+        let timeout = gst::message::Error::builder_from_error(glib::Error::new(
+            gio::IOErrorEnum::TimedOut,
+            "Socket I/O timed out",
+        ))
+        .build();
+        assert!(matches!(classify(timeout), PlayerError::Timeout { .. }));
+
+        let tls = gst::message::Error::builder_from_error(glib::Error::new(
+            gio::TlsError::BadCertificate,
+            "Unacceptable TLS certificate",
+        ))
+        .build();
+        assert!(matches!(classify(tls), PlayerError::Tls { .. }));
+
+        // Only the domain counts, not what the text mentions.
+        let failed = gst::message::Error::builder(gst::ResourceError::Failed, "timeout")
+            .debug("loading /etc/ssl/certificate.pem")
+            .build();
+        assert!(matches!(classify(failed), PlayerError::Pipeline { .. }));
+    }
+}
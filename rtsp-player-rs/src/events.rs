@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::PlayerError;
+
+/// Notifications published by an `RtspPlayer` through `RtspPlayer::subscribe`.
+#[derive(Clone, Debug)]
+pub enum PlayerEvent {
+    /// The pipeline reached a new state.
+    StateChanged(gst::State),
+    /// The connection was lost and the source is being rebuilt.
+    Reconnecting {
+        attempt: u32,
+    },
+    /// Data is flowing again after one or more `Reconnecting` events.
+    Reconnected,
+    /// The texture changed size, the widget showing it should relayout.
+    SizeChanged {
+        width: u32,
+        height: u32,
+    },
+    /// `RtspPlayer::start_recording` took effect.
+    RecordingStarted {
+        directory: PathBuf,
+    },
+    /// A recorded file is complete.
+    FileClosed {
+        path: PathBuf,
+        duration: Duration,
+    },
+    RecordingStopped,
+    /// Switched to another of `RtspPlayer::set_stream_variants`.
+    VariantSwitched {
+        index: usize,
+    },
+    /// The server ended the stream.
+    Eos,
+    Error(Arc<PlayerError>),
+}
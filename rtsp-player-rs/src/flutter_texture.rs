@@ -1,11 +1,9 @@
 // inspired by https://github.com/momentobooth/momentobooth/blob/main/rust/src/utils/flutter_texture.rs
 use crate::models::images::RawImage;
-use libloading::{
-    Library, Symbol, Error as LibError
-};
-use log::error;
+use libloading::{Error as LibError, Library, Symbol};
+use log::{debug, error};
 use std::ffi::{c_int, c_void};
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, Mutex};
 
 #[cfg(all(target_os = "linux"))]
 pub static TEXTURE_RGBA_RENDERER_PLUGIN: LazyLock<Result<Library, LibError>> =
@@ -31,14 +29,14 @@ pub type FlutterRgbaRendererPluginOnRgba = unsafe extern "C" fn(
 #[derive(Clone)]
 pub struct FlutterTexture {
     pub ptr: usize, // TextureRgba pointer in flutter native.
-    width: u32,
-    height: u32,
+    /// Shared between clones, so the copy held by the pipeline and the
+    /// application's copy agree on the current size.
+    size: Arc<Mutex<(u32, u32)>>,
     on_rgba_func: Option<Symbol<'static, FlutterRgbaRendererPluginOnRgba>>,
 }
 
 impl FlutterTexture {
-    pub fn new(
-        ptr: usize, width: u32, height: u32) -> Self {
+    pub fn new(ptr: usize, width: u32, height: u32) -> Self {
         let on_rgba_func = match &*TEXTURE_RGBA_RENDERER_PLUGIN {
             Ok(lib) => {
                 let find_sym_res = unsafe {
@@ -62,17 +60,23 @@ impl FlutterTexture {
         };
         Self {
             ptr,
-            width,
-            height,
+            size: Arc::new(Mutex::new((width, height))),
             on_rgba_func,
         }
     }
 }
 
 impl FlutterTexture {
-    pub fn set_size(&mut self, width: u32, height: u32) {
-        self.width = width;
-        self.height = height;
+    /// Sets the size, returning whether it changed.
+    pub fn set_size(&self, width: u32, height: u32) -> bool {
+        let mut size = self.size.lock().unwrap();
+        let changed = *size != (width, height);
+        *size = (width, height);
+        changed
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        *self.size.lock().unwrap()
     }
 
     pub fn on_rgba(&self, raw_image: &RawImage) {
@@ -80,8 +84,13 @@ impl FlutterTexture {
             return;
         }
 
-        if self.width != raw_image.width || self.height != raw_image.height {
-            return;
+        // Normally the size already follows the caps, but a frame is never
+        // dropped because of it.
+        if self.set_size(raw_image.width, raw_image.height) {
+            debug!(
+                "Texture resized to {}x{} by an incoming frame",
+                raw_image.width, raw_image.height
+            );
         }
 
         if let Some(func) = &self.on_rgba_func {
@@ -92,7 +101,7 @@ impl FlutterTexture {
                     raw_image.data.len() as _,
                     raw_image.width as _,
                     raw_image.height as _,
-                    0,
+                    raw_image.stride as _,
                 )
             };
         }
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use gst::prelude::*;
+use gst_gl::prelude::*;
+use log::warn;
+
+use crate::error::Result;
+use crate::utils::make_named_element;
+use crate::RenderUnix;
+
+/// Name of the `appsink` handed to `glsinkbin`.
+pub(crate) const GL_APPSINK_NAME: &str = "gl-appsink";
+
+/// A decoded frame living in a GL texture that can be sampled from the
+/// application's context.
+///
+/// The texture stays valid for as long as the frame is alive, so keep it
+/// around until the render pass that samples it has been submitted. Call
+/// `wait` before sampling it.
+pub struct GlFrame {
+    pub texture_id: u32,
+    pub width: u32,
+    pub height: u32,
+    /// The texture is a `GL_TEXTURE_EXTERNAL_OES` rather than a
+    /// `GL_TEXTURE_2D`.
+    pub is_external_oes: bool,
+    frame: gst_gl::GLVideoFrame<gst_gl::gl_video_frame::Readable>,
+}
+
+impl GlFrame {
+    /// Makes `app_context` wait for the GPU commands that produced the
+    /// texture. `app_context` has to be current on the calling thread; it
+    /// only queues the wait, so the CPU doesn't block.
+    pub fn wait(&self, app_context: &gst_gl::GLContext) {
+        if let Some(sync_meta) = self.frame.buffer().meta::<gst_gl::GLSyncMeta>() {
+            sync_meta.wait(app_context);
+        }
+    }
+}
+
+impl std::fmt::Debug for GlFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlFrame")
+            .field("texture_id", &self.texture_id)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("is_external_oes", &self.is_external_oes)
+            .finish()
+    }
+}
+
+/// Builds `glsinkbin sink=appsink` producing RGBA `GLMemory` in a context
+/// shared with `render`'s application context, and hands every sample to
+/// `on_frame` without reading it back.
+pub fn make_gl_texture_sink(
+    render: Arc<RenderUnix>,
+    on_frame: impl Fn(GlFrame) + Send + Sync + 'static,
+) -> Result<gst::Element> {
+    let appsink = gst_app::AppSink::builder()
+        .name(GL_APPSINK_NAME)
+        .max_buffers(1)
+        .drop(true)
+        .build();
+    let frame_render = render.clone();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |appsink| {
+                let sample = appsink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                match frame_render.build_frame(sample) {
+                    Some(frame) => on_frame(frame),
+                    None => warn!("Dropping sample without a GL texture"),
+                }
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+    render.build_video_sink(appsink.upcast_ref())
+}
+
+impl RenderUnix {
+    /// The application's context, as wrapped for GStreamer. Pass it to
+    /// `GlFrame::wait` on the render thread.
+    pub fn app_context(&self) -> &gst_gl::GLContext {
+        &self.app_context
+    }
+
+    /// The context GStreamer uploads and converts frames in, shared by every
+    /// pipeline rendering through this `RenderUnix`.
+    pub fn gst_context(&self) -> &gst_gl::GLContext {
+        &self.gst_context
+    }
+
+    /// Wraps `appsink` in a `glsinkbin` that outputs GL textures.
+    pub(crate) fn build_video_sink(&self, appsink: &gst::Element) -> Result<gst::Element> {
+        let caps = gst::Caps::builder("video/x-raw")
+            .features([gst_gl::CAPS_FEATURE_MEMORY_GL_MEMORY])
+            .field("format", gst_video::VideoFormat::Rgba.to_str())
+            .field("texture-target", gst::List::new(["2D", "external-oes"]))
+            .build();
+        appsink.set_property("caps", caps);
+
+        let glsinkbin = make_named_element("glsinkbin", "gl-texture-sink")?;
+        glsinkbin.set_property("sink", appsink);
+        // A bin hands its contexts to its children, so the GL elements
+        // inside pick up our display and share the application context
+        // instead of creating their own.
+        for context in self.gl_contexts() {
+            glsinkbin.set_context(&context);
+        }
+        Ok(glsinkbin)
+    }
+
+    /// Maps `sample` as a GL texture, marking where the GPU commands that
+    /// produced it end so `GlFrame::wait` can wait for them.
+    pub(crate) fn build_frame(&self, sample: gst::Sample) -> Option<GlFrame> {
+        let caps = sample.caps()?;
+        let info = gst_video::VideoInfo::from_caps(caps).ok()?;
+        let is_external_oes = caps
+            .structure(0)
+            .and_then(|s| s.get::<&str>("texture-target").ok())
+            .is_some_and(|target| target == "external-oes");
+        let buffer = sample.buffer_owned()?;
+
+        // The context the texture was produced in, normally `gst_context`.
+        let context = buffer
+            .peek_memory(0)
+            .downcast_memory_ref::<gst_gl::GLBaseMemory>()
+            .map(|memory| memory.context().clone())?;
+        if let Some(sync_meta) = buffer.meta::<gst_gl::GLSyncMeta>() {
+            sync_meta.set_sync_point(&context);
+        }
+
+        let frame = gst_gl::GLVideoFrame::from_buffer_readable(buffer, &info).ok()?;
+
+        Some(GlFrame {
+            texture_id: frame.texture_id(0).ok()?,
+            width: info.width(),
+            height: info.height(),
+            is_external_oes,
+            frame,
+        })
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, Utc};
 use num_derive::FromPrimitive;
 
 #[derive(Clone)]
@@ -7,15 +7,32 @@ pub struct RawImage {
     pub data: Vec<u8>,
     pub width: u32,
     pub height: u32,
+    /// Bytes per row in `data`, which may be more than `width * 4`.
+    pub stride: u32,
+    /// The camera's wall-clock time of the frame, from RTCP sender reports
+    /// or the ONVIF replay extension. `None` when the stream carries
+    /// neither.
+    pub captured_at: Option<DateTime<Utc>>,
 }
 
 impl RawImage {
     pub(crate) fn new_from_rgba_data(data: Vec<u8>, width: u32, height: u32) -> RawImage {
+        Self::new_from_rgba_data_with_stride(data, width, height, width * 4)
+    }
+
+    pub(crate) fn new_from_rgba_data_with_stride(
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+        stride: u32,
+    ) -> RawImage {
         Self {
             format: RawImageFormat::Rgba,
             data,
             width,
             height,
+            stride,
+            captured_at: None,
         }
     }
 }
@@ -33,7 +50,7 @@ pub enum MomentoBoothExifTag {
     MakerNote(String),
 }
 
-#[derive(FromPrimitive)]
+#[derive(Clone, Copy, Debug, FromPrimitive)]
 pub enum ExifOrientation {
     TopLeft = 1,
     TopRight = 2,
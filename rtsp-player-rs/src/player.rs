@@ -0,0 +1,1708 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use gst::glib;
+use gst::prelude::*;
+use log::{debug, error, info, warn};
+use tokio::sync::{broadcast, watch};
+
+use crate::audio::{make_audio_sink, resolve_audio_device, AudioDevice};
+use crate::auth::{redact_uri, Credentials, CredentialsProvider};
+use crate::codec::{
+    can_decode_audio, encoding_name_of, make_audio_decode_chain, make_decode_chain, media_type_of,
+    DecoderMode, VideoEncodeType,
+};
+use crate::config::{PlayerConfig, VideoSinkMode};
+use crate::error::{post_error, PlayerError, Result};
+use crate::events::PlayerEvent;
+use crate::flutter_texture::FlutterTexture;
+use crate::gl_texture::{make_gl_texture_sink, GlFrame};
+use crate::models::images::{ExifOrientation, MomentoBoothExifTag, RawImage};
+use crate::pool::{DecoderBudget, DecoderPermit};
+use crate::prebuffer::{Prebuffer, CLIP_STALL_TIMEOUT};
+use crate::recording::{RecordingBranch, RecordingConfig, FRAGMENT_CLOSED, FRAGMENT_OPENED};
+use crate::rgba::{make_rgba_sink, APPSINK_NAME};
+use crate::snapshot::{encode, raw_image_from_last_sample, SnapshotFormat};
+use crate::state::{AudioParams, PlayerState, VideoParams};
+use crate::stats::{RtpTotals, StatsSampler, StreamStats};
+use crate::timestamps::add_onvif_probe;
+use crate::tracks::{TrackSelection, Tracks};
+use crate::utils::{link_many, make_gs_element};
+use crate::variants::{
+    drop_until_keyframe, pick_variant, PendingSwitch, StreamVariant, VARIANT_READY,
+};
+use crate::watcher::BusWatcher;
+use crate::RenderUnix;
+
+/// How often the position in `PlayerState` is refreshed.
+const POSITION_INTERVAL: Duration = Duration::from_millis(250);
+/// How often `StreamStats` are refreshed.
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a reconnect, or dropping the player, waits for the recording to
+/// close its file, and how often it checks.
+const RECORDING_CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+const RECORDING_CLOSE_POLL: Duration = Duration::from_millis(50);
+
+/// A long-lived handle over a single RTSP pipeline.
+///
+/// The pipeline bus is watched on an internal thread, shared by the players
+/// of a `PlayerPool`, so none of the methods block beyond the state change
+/// itself. Dropping the player brings the pipeline down to `State::Null`.
+pub struct RtspPlayer {
+    inner: Arc<PlayerInner>,
+    /// The bus watch and the position timer, on `PlayerInner::watcher`.
+    sources: Vec<glib::Source>,
+}
+
+/// State shared between the player handle, the watcher thread and the
+/// `rtspsrc` callbacks.
+struct PlayerInner {
+    uri: String,
+    /// `uri` without user info, for logs and element names.
+    display_uri: String,
+    config: PlayerConfig,
+    /// Unset when the application brought its own sink.
+    texture: Option<FlutterTexture>,
+    pipeline: gst::Pipeline,
+    /// First element of the sink side, the video branches link onto it.
+    video_sink: gst::Element,
+    /// Only set in `VideoSinkMode::Gl`.
+    glimagesink: Option<gst::Element>,
+    /// The `RenderUnix` context frames are uploaded in, in
+    /// `VideoSinkMode::GlTexture`.
+    gl_context: Option<gst_gl::GLContext>,
+    /// `rtspsrc` and every element of its dynamic branches, so a reconnect
+    /// can drop them without touching the sink side.
+    source_elements: Mutex<Vec<gst::Element>>,
+    /// `volume` and sink of the audio branch, while there is one.
+    audio_volume: Mutex<Option<gst::Element>>,
+    audio_sink: Mutex<Option<gst::Element>>,
+    /// The output behind `PlayerState::audio_device`, `None` for the
+    /// default one.
+    audio_output: Mutex<Option<gst::Device>>,
+    /// Pre-event buffer for `save_clip`, if enabled in the config.
+    prebuffer: Option<Arc<Prebuffer>>,
+    /// Set while recording, including across reconnects.
+    recording: Mutex<Option<RecordingConfig>>,
+    /// Tee in front of the video decoder, while there is a video branch.
+    recording_tee: Mutex<Option<gst::Element>>,
+    recording_branch: Mutex<Option<RecordingBranch>>,
+    /// Stopped branches waiting for their last file to be closed.
+    finishing_branches: Mutex<Vec<RecordingBranch>>,
+    /// Main/sub streams picked from by render size.
+    variants: Mutex<Vec<StreamVariant>>,
+    /// Index into `variants` of the playing source, `None` for `uri`.
+    active_variant: Mutex<Option<usize>>,
+    render_size: Mutex<Option<(u32, u32)>>,
+    pending_switch: Mutex<Option<PendingSwitch>>,
+    decoder_budget: Option<Arc<DecoderBudget>>,
+    /// Held from leaving `gst::State::Null` until stopped again.
+    decoder_permit: Mutex<Option<DecoderPermit>>,
+    /// The state requested by the application, restored after a reconnect.
+    target_state: Mutex<gst::State>,
+    watcher: Arc<BusWatcher>,
+    /// Consecutive reconnect attempts, reset once data flows again.
+    reconnect_attempt: AtomicU32,
+    /// Fires once the backoff before the next attempt is over.
+    reconnect_timer: Mutex<Option<glib::Source>>,
+    /// Running time at which each recorded file was opened.
+    fragment_starts: Mutex<HashMap<String, gst::ClockTime>>,
+    credentials: Mutex<Option<Credentials>>,
+    credentials_provider: Mutex<Option<Arc<CredentialsProvider>>>,
+    events: broadcast::Sender<PlayerEvent>,
+    state: watch::Sender<PlayerState>,
+    stats: watch::Sender<StreamStats>,
+    stats_sampler: Mutex<StatsSampler>,
+    /// Video frames out of the parser and into the video sink.
+    frames_received: Arc<AtomicU64>,
+    frames_rendered: Arc<AtomicU64>,
+}
+
+/// Callback receiving frames in `VideoSinkMode::GlTexture`.
+pub(crate) type GlFrameCallback = Box<dyn Fn(GlFrame) + Send + Sync>;
+
+/// What the video branches end in.
+pub(crate) enum SinkSetup {
+    /// Follow `PlayerConfig::video_sink`, rendering to the texture.
+    Texture(FlutterTexture),
+    GlRender(Arc<RenderUnix>, GlFrameCallback),
+    /// An element supplied by the application.
+    Element(gst::Element),
+}
+
+impl RtspPlayer {
+    /// Builds the pipeline for `uri` without starting it.
+    pub fn new(uri: String, config: PlayerConfig, texture: FlutterTexture) -> Result<Self> {
+        Self::build(uri, config, SinkSetup::Texture(texture), None, None)
+    }
+
+    /// Builds a player in `VideoSinkMode::GlTexture`, handing every frame to
+    /// `on_frame` as a texture in a context shared with `render`'s
+    /// application context.
+    pub fn with_gl_render(
+        uri: String,
+        config: PlayerConfig,
+        render: Arc<RenderUnix>,
+        on_frame: impl Fn(GlFrame) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let config = PlayerConfig {
+            video_sink: VideoSinkMode::GlTexture,
+            ..config
+        };
+        Self::build(
+            uri,
+            config,
+            SinkSetup::GlRender(render, Box::new(on_frame)),
+            None,
+            None,
+        )
+    }
+
+    /// Builds a player whose video ends in `sink`, which needs a `sink` pad
+    /// accepting raw video. `PlayerConfig::video_sink` is ignored.
+    pub fn with_video_sink(uri: String, config: PlayerConfig, sink: gst::Element) -> Result<Self> {
+        Self::build(uri, config, SinkSetup::Element(sink), None, None)
+    }
+
+    /// `decoder_budget` and `watcher` are shared by the players of a
+    /// `PlayerPool`. Without a `watcher` the player gets a thread of its own.
+    pub(crate) fn build(
+        uri: String,
+        config: PlayerConfig,
+        sink_setup: SinkSetup,
+        decoder_budget: Option<Arc<DecoderBudget>>,
+        watcher: Option<Arc<BusWatcher>>,
+    ) -> Result<Self> {
+        let display_uri = redact_uri(&uri).into_owned();
+        let pipeline = gst::Pipeline::builder()
+            .name(format!("rtsp pipeline {}", display_uri))
+            .build();
+        let mut texture = None;
+        let mut gl_context = None;
+        let (video_sink, glimagesink) = match sink_setup {
+            SinkSetup::Texture(flutter_texture) => {
+                let sinks = match config.video_sink {
+                    VideoSinkMode::Gl => {
+                        let glupload = make_gs_element("glupload")?;
+                        let glimagesink = make_gs_element("glimagesink")?;
+                        pipeline.add_many([&glupload, &glimagesink])?;
+                        link_many(&[&glupload, &glimagesink])?;
+                        (glupload, Some(glimagesink))
+                    }
+                    VideoSinkMode::Rgba => {
+                        let flutter_texture = flutter_texture.clone();
+                        let rgba_sink = make_rgba_sink(config.texture_size, move |image| {
+                            flutter_texture.on_rgba(&image)
+                        })?;
+                        pipeline.add(&rgba_sink)?;
+                        (rgba_sink, None)
+                    }
+                    // Needs a `RenderUnix`, see `with_gl_render`.
+                    VideoSinkMode::GlTexture => return Err(PlayerError::GlContextUnavailable),
+                };
+                texture = Some(flutter_texture);
+                sinks
+            }
+            SinkSetup::GlRender(render, on_frame) => {
+                render.install_sync_handler(&pipeline.bus().unwrap());
+                gl_context = Some(render.gst_context().clone());
+                let gl_sink = make_gl_texture_sink(render, on_frame)?;
+                pipeline.add(&gl_sink)?;
+                (gl_sink, None)
+            }
+            SinkSetup::Element(sink) => {
+                pipeline.add(&sink)?;
+                (sink, None)
+            }
+        };
+
+        let (events, _) = broadcast::channel(32);
+        let credentials = config.credentials.clone();
+        let prebuffer = config
+            .prebuffer
+            .clone()
+            .map(|prebuffer| Arc::new(Prebuffer::new(prebuffer, events.clone())));
+        let inner = Arc::new(PlayerInner {
+            uri,
+            display_uri,
+            config,
+            texture,
+            pipeline,
+            video_sink,
+            glimagesink,
+            gl_context,
+            source_elements: Mutex::new(Vec::new()),
+            audio_volume: Mutex::new(None),
+            audio_sink: Mutex::new(None),
+            audio_output: Mutex::new(None),
+            prebuffer,
+            recording: Mutex::new(None),
+            recording_tee: Mutex::new(None),
+            recording_branch: Mutex::new(None),
+            finishing_branches: Mutex::new(Vec::new()),
+            variants: Mutex::new(Vec::new()),
+            active_variant: Mutex::new(None),
+            render_size: Mutex::new(None),
+            pending_switch: Mutex::new(None),
+            decoder_budget,
+            decoder_permit: Mutex::new(None),
+            target_state: Mutex::new(gst::State::Null),
+            watcher: watcher.unwrap_or_else(|| Arc::new(BusWatcher::new("rtsp-player-bus"))),
+            reconnect_attempt: AtomicU32::new(0),
+            reconnect_timer: Mutex::new(None),
+            fragment_starts: Mutex::new(HashMap::new()),
+            credentials: Mutex::new(credentials),
+            credentials_provider: Mutex::new(None),
+            events,
+            state: watch::Sender::new(PlayerState::default()),
+            stats: watch::Sender::new(StreamStats::default()),
+            stats_sampler: Mutex::new(StatsSampler::default()),
+            frames_received: Arc::new(AtomicU64::new(0)),
+            frames_rendered: Arc::new(AtomicU64::new(0)),
+        });
+        inner.add_source()?;
+
+        let frames_rendered = inner.frames_rendered.clone();
+        inner.video_sink.static_pad("sink").unwrap().add_probe(
+            gst::PadProbeType::BUFFER,
+            move |_, _| {
+                frames_rendered.fetch_add(1, Ordering::Relaxed);
+                gst::PadProbeReturn::Ok
+            },
+        );
+
+        let inner_weak = Arc::downgrade(&inner);
+        inner
+            .video_sink
+            .static_pad("sink")
+            .unwrap()
+            .connect_notify(Some("caps"), move |pad, _| {
+                let Some(inner) = Weak::upgrade(&inner_weak) else {
+                    return;
+                };
+                let Some(params) = pad
+                    .current_caps()
+                    .and_then(|caps| VideoParams::from_caps(&caps))
+                else {
+                    return;
+                };
+                inner.state.send_modify(|state| {
+                    state.width = params.w;
+                    state.height = params.h;
+                    state.video_params = params;
+                });
+            });
+
+        let inner_weak = Arc::downgrade(&inner);
+        inner
+            .texture_pad()
+            .connect_notify(Some("caps"), move |pad, _| {
+                let Some(inner) = Weak::upgrade(&inner_weak) else {
+                    return;
+                };
+                if let Some((width, height)) = pad
+                    .current_caps()
+                    .and_then(|caps| VideoParams::from_caps(&caps))
+                    .and_then(|params| params.w.zip(params.h))
+                {
+                    inner.resize_texture(width, height);
+                }
+            });
+
+        let bus = inner
+            .pipeline
+            .bus()
+            .expect("Pipeline without bus. Shouldn't happen!");
+        let inner_weak = Arc::downgrade(&inner);
+        let bus_watch = inner.watcher.watch_bus(&bus, move |_, msg| {
+            if let Some(inner) = Weak::upgrade(&inner_weak) {
+                handle_message(&inner, msg);
+            }
+            glib::ControlFlow::Continue
+        });
+        let inner_weak = Arc::downgrade(&inner);
+        let mut last_stats_update = Instant::now();
+        let timer = inner.watcher.add_timeout(POSITION_INTERVAL, move || {
+            let Some(inner) = Weak::upgrade(&inner_weak) else {
+                return glib::ControlFlow::Break;
+            };
+            inner.update_position();
+            if last_stats_update.elapsed() >= STATS_INTERVAL {
+                inner.update_stats();
+                last_stats_update = Instant::now();
+            }
+            glib::ControlFlow::Continue
+        });
+
+        Ok(Self {
+            inner,
+            sources: vec![bus_watch, timer],
+        })
+    }
+
+    /// The URI as given, including any user info. Use `redact_uri` before
+    /// logging it.
+    pub fn uri(&self) -> &str {
+        &self.inner.uri
+    }
+
+    pub fn play(&self) -> Result<()> {
+        self.inner.set_target_state(gst::State::Playing)
+    }
+
+    pub fn pause(&self) -> Result<()> {
+        self.inner.set_target_state(gst::State::Paused)
+    }
+
+    /// Stops the stream. The player can be started again with `play`.
+    pub fn stop(&self) -> Result<()> {
+        self.inner.set_target_state(gst::State::Null)
+    }
+
+    /// Returns the current state of the underlying pipeline.
+    pub fn state(&self) -> gst::State {
+        self.inner.pipeline.current_state()
+    }
+
+    /// Replaces the credentials used from the next connection attempt on.
+    pub fn set_credentials(&self, credentials: Option<Credentials>) {
+        *self.inner.credentials.lock().unwrap() = credentials;
+    }
+
+    /// Installs a hook asked for credentials whenever the server rejects the
+    /// current ones, so secrets only need to be fetched when required.
+    pub fn set_credentials_provider(
+        &self,
+        provider: impl Fn(&str, Option<&Credentials>) -> Option<Credentials> + Send + Sync + 'static,
+    ) {
+        *self.inner.credentials_provider.lock().unwrap() = Some(Arc::new(provider));
+    }
+
+    /// Sets the volume in percent, 100 leaving the audio unchanged.
+    pub fn set_volume(&self, volume: f64) {
+        let volume = volume.clamp(0.0, 1000.0);
+        if let Some(element) = self.inner.audio_volume.lock().unwrap().as_ref() {
+            element.set_property("volume", volume / 100.0);
+        }
+        self.inner.state.send_modify(|state| state.volume = volume);
+    }
+
+    pub fn set_mute(&self, muted: bool) {
+        if let Some(element) = self.inner.audio_volume.lock().unwrap().as_ref() {
+            element.set_property("mute", muted);
+        }
+        self.inner.state.send_modify(|state| state.muted = muted);
+    }
+
+    /// Routes the audio to `device`, see `audio::audio_devices`. Takes
+    /// effect immediately if the stream has audio.
+    pub fn set_audio_device(&self, device: AudioDevice) -> Result<()> {
+        *self.inner.audio_output.lock().unwrap() = resolve_audio_device(&device)?;
+        self.inner.swap_audio_sink()?;
+        self.inner
+            .state
+            .send_modify(|state| state.audio_device = device);
+        Ok(())
+    }
+
+    /// Switches the video stream. The source is set up again, as RTSP has
+    /// no way of adding a stream to a running session.
+    pub fn select_video_track(&self, selection: TrackSelection) -> Result<()> {
+        self.inner
+            .state
+            .send_modify(|state| state.track.video = selection);
+        self.inner.reselect_tracks()
+    }
+
+    /// Switches the audio stream, setting the source up again like
+    /// `select_video_track`.
+    pub fn select_audio_track(&self, selection: TrackSelection) -> Result<()> {
+        self.inner
+            .state
+            .send_modify(|state| state.track.audio = selection);
+        self.inner.reselect_tracks()
+    }
+
+    /// The last rendered frame as RGBA.
+    pub fn snapshot_raw(&self) -> Result<RawImage> {
+        let sample = self.inner.last_sample().ok_or(PlayerError::Snapshot {
+            message: "No frame has been rendered yet".to_string(),
+        })?;
+        raw_image_from_last_sample(&sample)
+    }
+
+    /// The last rendered frame encoded as `format`, tagged with the capture
+    /// time and the (redacted) stream URI. The capture time is the camera's
+    /// clock when the stream carries it, the local clock otherwise.
+    /// `quality` applies to JPEG only.
+    pub fn snapshot(&self, format: SnapshotFormat, quality: u8) -> Result<Vec<u8>> {
+        let image = self.snapshot_raw()?;
+        let created = image
+            .captured_at
+            .map(|at| at.with_timezone(&chrono::Local))
+            .unwrap_or_else(chrono::Local::now);
+        let tags = vec![
+            MomentoBoothExifTag::CreateDate(created),
+            MomentoBoothExifTag::ImageDescription(self.inner.display_uri.clone()),
+            MomentoBoothExifTag::Software(format!(
+                "{} {}",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            )),
+            MomentoBoothExifTag::Orientation(ExifOrientation::TopLeft),
+        ];
+        encode(&image, format, quality, tags)
+    }
+
+    /// Records the stream as received, without re-encoding, next to the
+    /// live view. A running recording is finished first.
+    pub fn start_recording(&self, config: RecordingConfig) -> Result<()> {
+        std::fs::create_dir_all(&config.directory).map_err(|err| PlayerError::Recording {
+            message: format!("{}: {}", config.directory.display(), err),
+        })?;
+        self.inner.finish_recording();
+        let directory = config.directory.clone();
+        *self.inner.recording.lock().unwrap() = Some(config);
+        self.inner.attach_recording()?;
+        self.inner.emit(PlayerEvent::RecordingStarted { directory });
+        Ok(())
+    }
+
+    /// Finishes the current file. `PlayerEvent::FileClosed` follows once it
+    /// has been written.
+    pub fn stop_recording(&self) {
+        if self.inner.recording.lock().unwrap().take().is_some() {
+            self.inner.finish_recording();
+            self.inner.emit(PlayerEvent::RecordingStopped);
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.inner.recording.lock().unwrap().is_some()
+    }
+
+    /// Saves an incident clip to an MP4 at `path`: the buffered stream from
+    /// the last keyframe at least `pre` ago, followed by the next `post` of
+    /// the stream. Returns once the clip is started;
+    /// `PlayerEvent::FileClosed` follows once it has been written, at the
+    /// latest `CLIP_STALL_TIMEOUT` after `post` if the stream stalls.
+    ///
+    /// Needs `PlayerConfig::prebuffer`, and covers at most its
+    /// `max_duration` before now.
+    pub fn save_clip(&self, pre: Duration, post: Duration, path: impl AsRef<Path>) -> Result<()> {
+        let prebuffer = self
+            .inner
+            .prebuffer
+            .as_ref()
+            .ok_or_else(|| PlayerError::Recording {
+                message: "Pre-event buffering is disabled".to_string(),
+            })?;
+        let path = path.as_ref().to_path_buf();
+        prebuffer.save_clip(pre, post, path.clone())?;
+
+        let prebuffer_weak = Arc::downgrade(prebuffer);
+        self.inner
+            .watcher
+            .add_timeout(post + CLIP_STALL_TIMEOUT, move || {
+                if let Some(prebuffer) = Weak::upgrade(&prebuffer_weak) {
+                    prebuffer.close_clip(&path);
+                }
+                glib::ControlFlow::Break
+            });
+        Ok(())
+    }
+
+    /// Sets the encodings the camera offers, e.g. its main stream and
+    /// substream, for `set_render_size` to pick from. The stream playing
+    /// stays until a render size asks for another one.
+    pub fn set_stream_variants(&self, variants: Vec<StreamVariant>) -> Result<()> {
+        let current_uri = self.inner.current_uri();
+        *self.inner.active_variant.lock().unwrap() = variants
+            .iter()
+            .position(|variant| variant.uri == current_uri);
+        *self.inner.variants.lock().unwrap() = variants;
+        self.inner.cancel_switch();
+        self.inner.update_variant()
+    }
+
+    /// Sets the size the video is shown at, in physical pixels, switching to
+    /// the smallest stream variant that covers it.
+    ///
+    /// The new stream is connected and decoded next to the playing one,
+    /// which keeps showing until the new one's first frame is ready, so
+    /// there is no black frame in between.
+    pub fn set_render_size(&self, width: u32, height: u32) -> Result<()> {
+        *self.inner.render_size.lock().unwrap() = Some((width, height));
+        self.inner.update_variant()
+    }
+
+    /// Returns a receiver holding the latest `StreamStats`, refreshed every
+    /// second.
+    pub fn watch_stats(&self) -> watch::Receiver<StreamStats> {
+        self.inner.stats.subscribe()
+    }
+
+    /// Returns a receiver that always holds the latest `PlayerState`.
+    pub fn watch_state(&self) -> watch::Receiver<PlayerState> {
+        self.inner.state.subscribe()
+    }
+
+    /// Subscribes to state changes, reconnects and errors of this player.
+    pub fn subscribe(&self) -> broadcast::Receiver<PlayerEvent> {
+        self.inner.events.subscribe()
+    }
+
+    /// The GL context this player's pipeline uploads frames in, the one
+    /// of its `RenderUnix`. Only set in `VideoSinkMode::GlTexture`.
+    pub fn gl_context(&self) -> Option<gst_gl::GLContext> {
+        self.inner.gl_context.clone()
+    }
+}
+
+impl Drop for RtspPlayer {
+    fn drop(&mut self) {
+        // Clips and the recording get to finish their files first.
+        if let Some(prebuffer) = &self.inner.prebuffer {
+            prebuffer.reset();
+        }
+        self.inner.finish_recording();
+        let deadline = Instant::now() + RECORDING_CLOSE_TIMEOUT;
+        while !self.inner.finishing_branches.lock().unwrap().is_empty() && Instant::now() < deadline
+        {
+            std::thread::sleep(RECORDING_CLOSE_POLL);
+        }
+        self.inner.drop_finishing_branches();
+
+        if let Err(err) = self.inner.pipeline.set_state(gst::State::Null) {
+            error!(
+                "Failed to stop pipeline for {}: {:?}",
+                self.inner.display_uri, err
+            );
+        }
+        for source in &self.sources {
+            source.destroy();
+        }
+        if let Some(timer) = self.inner.reconnect_timer.lock().unwrap().take() {
+            timer.destroy();
+        }
+    }
+}
+
+impl PlayerInner {
+    fn set_target_state(&self, state: gst::State) -> Result<()> {
+        if let Some(budget) = &self.decoder_budget {
+            let mut permit = self.decoder_permit.lock().unwrap();
+            if state == gst::State::Null {
+                permit.take();
+            } else if permit.is_none() {
+                *permit = Some(budget.try_acquire()?);
+            }
+        }
+        *self.target_state.lock().unwrap() = state;
+        self.pipeline.set_state(state)?;
+        Ok(())
+    }
+
+    fn emit(&self, event: PlayerEvent) {
+        // Nobody listening is not an error.
+        let _ = self.events.send(event);
+    }
+
+    /// The `last-sample` of whichever sink ends the video branch.
+    fn last_sample(&self) -> Option<gst::Sample> {
+        let sink = match (
+            &self.glimagesink,
+            self.video_sink.downcast_ref::<gst::Bin>(),
+        ) {
+            (Some(glimagesink), _) => Some(glimagesink.clone()),
+            (None, Some(bin)) => bin
+                .iterate_all_by_element_factory_name("appsink")
+                .next()
+                .ok()
+                .flatten()
+                .or_else(|| bin.iterate_sinks().next().ok().flatten()),
+            (None, None) => Some(self.video_sink.clone()),
+        }?;
+        if sink.find_property("last-sample").is_none() {
+            return None;
+        }
+        sink.property::<Option<gst::Sample>>("last-sample")
+    }
+
+    /// Whether `src` was removed from the pipeline already, like a source
+    /// whose failure was handled through an error it posted earlier.
+    fn is_removed(&self, src: Option<&gst::Object>) -> bool {
+        src.is_some_and(|src| {
+            src != self.pipeline.upcast_ref::<gst::Object>() && !src.has_as_ancestor(&self.pipeline)
+        })
+    }
+
+    /// Whether `src` is the sink rendering the video, the only element
+    /// whose QoS counts frames; decoders and converters post their own.
+    fn is_video_sink(&self, src: Option<&gst::Object>) -> bool {
+        let Some(element) = src.and_then(|src| src.downcast_ref::<gst::Element>()) else {
+            return false;
+        };
+        if !element.element_flags().contains(gst::ElementFlags::SINK) {
+            return false;
+        }
+        let sink = self.glimagesink.as_ref().unwrap_or(&self.video_sink);
+        element == sink || element.has_as_ancestor(sink)
+    }
+
+    /// The pad whose caps match what ends up in the texture.
+    fn texture_pad(&self) -> gst::Pad {
+        self.video_sink
+            .downcast_ref::<gst::Bin>()
+            .and_then(|bin| bin.by_name(APPSINK_NAME))
+            .unwrap_or_else(|| self.video_sink.clone())
+            .static_pad("sink")
+            .unwrap()
+    }
+
+    fn resize_texture(&self, width: u32, height: u32) {
+        self.state.send_if_modified(|state| {
+            let changed =
+                state.texture_width != Some(width) || state.texture_height != Some(height);
+            state.texture_width = Some(width);
+            state.texture_height = Some(height);
+            changed
+        });
+        let Some(texture) = &self.texture else {
+            return;
+        };
+        if texture.set_size(width, height) {
+            info!("Texture resized to {}x{}", width, height);
+            self.emit(PlayerEvent::SizeChanged { width, height });
+        }
+    }
+
+    /// Creates a fresh `rtspsrc` and adds it to the pipeline. Its branches
+    /// are built once it exposes pads.
+    fn add_source(self: &Arc<Self>) -> Result<()> {
+        let source = self.make_source(&self.current_uri())?;
+        self.pipeline.add(&source)?;
+        self.source_elements.lock().unwrap().push(source);
+        Ok(())
+    }
+
+    /// The URI of the active stream variant, or `uri`.
+    fn current_uri(&self) -> String {
+        let variants = self.variants.lock().unwrap();
+        self.active_variant
+            .lock()
+            .unwrap()
+            .and_then(|index| variants.get(index))
+            .map_or_else(|| self.uri.clone(), |variant| variant.uri.clone())
+    }
+
+    fn make_source(self: &Arc<Self>, uri: &str) -> Result<gst::Element> {
+        let source = gst::ElementFactory::make("rtspsrc")
+            .property("location", uri)
+            .property("is-live", true)
+            .build()
+            .map_err(|_| PlayerError::ElementMissing {
+                factory: "rtspsrc".to_string(),
+            })?;
+        self.config.apply_to_rtspsrc(&source);
+        if let Some(tls) = &self.config.tls {
+            tls.apply_to_rtspsrc(&source)?;
+        }
+        if let Some(credentials) = self.credentials.lock().unwrap().as_ref() {
+            source.set_property("user-id", credentials.user());
+            source.set_property("user-pw", credentials.password());
+        }
+
+        let inner_weak = Arc::downgrade(self);
+        source.connect("on-sdp", false, move |args| {
+            if let (Some(inner), Ok(source), Ok(sdp)) = (
+                Weak::upgrade(&inner_weak),
+                args[0].get::<gst::Element>(),
+                args[1].get::<gst_sdp::SDPMessage>(),
+            ) {
+                inner.on_sdp(&source, Tracks::from_sdp(&sdp));
+            }
+            None
+        });
+
+        let inner_weak = Arc::downgrade(self);
+        source.connect("select-stream", false, move |args| {
+            let selected = match (
+                Weak::upgrade(&inner_weak),
+                args[0].get::<gst::Element>(),
+                args[1].get::<u32>(),
+                args[2].get::<gst::Caps>(),
+            ) {
+                (Some(inner), Ok(source), Ok(index), Ok(caps)) => {
+                    inner.is_stream_selected(&source, index, &caps)
+                }
+                _ => true,
+            };
+            Some(selected.to_value())
+        });
+
+        let inner_weak = Arc::downgrade(self);
+        source.connect_pad_added(move |self_source, pad| {
+            let Some(inner) = Weak::upgrade(&inner_weak) else {
+                return;
+            };
+            if let Err(err) = inner.link_source_pad(self_source, pad) {
+                post_error(self_source, err);
+            }
+        });
+        Ok(source)
+    }
+
+    /// Publishes the tracks `source` advertises, or keeps them with the
+    /// pending switch until it is swapped in.
+    fn on_sdp(&self, source: &gst::Element, tracks: Tracks) {
+        debug!("Server advertises {:?}", tracks);
+        let mut pending_switch = self.pending_switch.lock().unwrap();
+        if let Some(pending) = pending_switch
+            .as_mut()
+            .filter(|pending| pending.source == *source)
+        {
+            pending.tracks = Some(tracks);
+            return;
+        }
+        drop(pending_switch);
+        self.state.send_modify(|state| state.tracks = tracks);
+    }
+
+    /// Answers `rtspsrc`'s `select-stream`, so unselected streams are never
+    /// set up and their data never sent.
+    fn is_stream_selected(&self, source: &gst::Element, index: u32, caps: &gst::CapsRef) -> bool {
+        let Some(media) = caps
+            .structure(0)
+            .and_then(|s| s.get::<String>("media").ok())
+        else {
+            return true;
+        };
+        if media == "audio" && self.config.audio {
+            let encoding = caps
+                .structure(0)
+                .and_then(|s| s.get::<&str>("encoding-name").ok());
+            if !encoding.is_some_and(can_decode_audio) {
+                info!(
+                    "Skipping audio stream {}, can't decode {:?}",
+                    index, encoding
+                );
+                return false;
+            }
+        }
+        let pending_tracks = self
+            .pending_switch
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|pending| pending.source == *source)
+            .and_then(|pending| pending.tracks.clone());
+        let state = self.state.borrow();
+        let tracks = pending_tracks.as_ref().unwrap_or(&state.tracks);
+        // Without an SDP there is nothing to choose from.
+        if *tracks == Tracks::default() {
+            return media == "video" || (media == "audio" && self.config.audio);
+        }
+        let selection = match media.as_str() {
+            "video" => state.track.video,
+            "audio" if self.config.audio => state.track.audio,
+            _ => TrackSelection::No,
+        };
+        let selected = tracks.is_selected(&media, index, selection);
+        debug!("Stream {} ({}) selected: {}", index, media, selected);
+        selected
+    }
+
+    /// Hangs a recording branch off the video tee, if recording and there
+    /// is a video branch to record.
+    fn attach_recording(&self) -> Result<()> {
+        let recording = self.recording.lock().unwrap();
+        let (Some(config), Some(tee)) = (
+            recording.as_ref(),
+            self.recording_tee.lock().unwrap().clone(),
+        ) else {
+            return Ok(());
+        };
+        let mut branch = self.recording_branch.lock().unwrap();
+        if branch.is_none() {
+            *branch = Some(RecordingBranch::attach(&self.pipeline, &tee, config)?);
+        }
+        Ok(())
+    }
+
+    /// Feeds the pre-event buffer from `tee`, as part of the video branch.
+    fn attach_prebuffer(&self, prebuffer: &Arc<Prebuffer>, tee: &gst::Element) -> Result<()> {
+        let branch = prebuffer.make_branch()?;
+        self.pipeline.add_many(&branch)?;
+        self.source_elements
+            .lock()
+            .unwrap()
+            .extend(branch.iter().cloned());
+        link_many(&[&branch[0], &branch[1]])?;
+        let tee_pad = tee
+            .request_pad_simple("src_%u")
+            .ok_or_else(|| PlayerError::link_failed(tee, &branch[0]))?;
+        tee_pad
+            .link(&branch[0].static_pad("sink").unwrap())
+            .map_err(|_| PlayerError::link_failed(tee, &branch[0]))?;
+        for element in &branch {
+            element.sync_state_with_parent()?;
+        }
+        Ok(())
+    }
+
+    fn finish_recording(&self) {
+        if let Some(branch) = self.recording_branch.lock().unwrap().take() {
+            branch.finish();
+            self.finishing_branches.lock().unwrap().push(branch);
+        }
+    }
+
+    /// Reports closed files and drops finished branches.
+    fn on_fragment_closed(
+        &self,
+        splitmux: Option<&gst::Object>,
+        path: PathBuf,
+        duration: Duration,
+    ) {
+        info!("Recorded {} ({:?})", path.display(), duration);
+        self.emit(PlayerEvent::FileClosed { path, duration });
+
+        let mut finishing = self.finishing_branches.lock().unwrap();
+        if let Some(index) = finishing
+            .iter()
+            .position(|branch| Some(branch.splitmux.upcast_ref::<gst::Object>()) == splitmux)
+        {
+            finishing.remove(index).remove(&self.pipeline);
+        }
+    }
+
+    /// Drops branches still finishing once the pipeline has stopped, as
+    /// their files will never be closed.
+    fn drop_finishing_branches(&self) {
+        for branch in self.finishing_branches.lock().unwrap().drain(..) {
+            warn!(
+                "Recording of {} stopped before its file was closed",
+                self.display_uri
+            );
+            branch.remove(&self.pipeline);
+        }
+    }
+
+    /// Drops `rtspsrc` and its branches from the pipeline. The recording is
+    /// finished rather than cut and attached again with the new video
+    /// branch.
+    fn remove_source(&self) {
+        self.cancel_switch();
+        self.finish_recording();
+        self.recording_tee.lock().unwrap().take();
+        if let Some(prebuffer) = &self.prebuffer {
+            prebuffer.reset();
+        }
+        for element in self.source_elements.lock().unwrap().drain(..) {
+            let _ = element.set_state(gst::State::Null);
+            if let Err(err) = self.pipeline.remove(&element) {
+                warn!("Failed to remove {}: {}", element.name(), err);
+            }
+        }
+        self.audio_volume.lock().unwrap().take();
+        self.audio_sink.lock().unwrap().take();
+    }
+
+    fn update_stats(&self) {
+        let totals = self
+            .source_elements
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|element| {
+                element
+                    .factory()
+                    .is_some_and(|factory| factory.name() == "rtspsrc")
+            })
+            .map(RtpTotals::from_source)
+            .unwrap_or_default();
+        let mut stats = self.stats_sampler.lock().unwrap().sample(
+            Instant::now(),
+            totals,
+            self.frames_received.load(Ordering::Relaxed),
+            self.frames_rendered.load(Ordering::Relaxed),
+        );
+        {
+            let state = self.state.borrow();
+            stats.latency = state.latency;
+            stats.frames_dropped = state.frames_dropped;
+        }
+        self.stats.send_replace(stats);
+    }
+
+    fn update_position(&self) {
+        let Some(position) = self.pipeline.query_position::<gst::ClockTime>() else {
+            return;
+        };
+        let position = Duration::from_nanos(position.nseconds());
+        self.state.send_if_modified(|state| {
+            let changed = state.position != position;
+            state.position = position;
+            changed
+        });
+    }
+
+    /// Rebuilds the source right away, keeping the sink side untouched.
+    fn restart_source(self: &Arc<Self>) -> Result<()> {
+        let target_state = *self.target_state.lock().unwrap();
+        self.pipeline.set_state(gst::State::Null)?;
+        self.remove_source();
+        self.add_source()?;
+        self.pipeline.set_state(target_state)?;
+        Ok(())
+    }
+
+    /// Tears down the source and schedules a new one according to the
+    /// reconnect policy.
+    fn reconnect(self: &Arc<Self>) {
+        if self.config.reconnect.is_none() {
+            if let Err(err) = self.set_target_state(gst::State::Null) {
+                warn!("Failed to stop {}: {}", self.display_uri, err);
+            }
+            return;
+        }
+        if *self.target_state.lock().unwrap() == gst::State::Null
+            || self.reconnect_timer.lock().unwrap().is_some()
+        {
+            return;
+        }
+
+        let attempt = self.reconnect_attempt.fetch_add(1, Ordering::Relaxed) + 1;
+        // The recording gets to close its file before the pipeline goes down.
+        self.finish_recording();
+        if self.finishing_branches.lock().unwrap().is_empty() {
+            self.schedule_retry(attempt);
+            return;
+        }
+        let deadline = Instant::now() + RECORDING_CLOSE_TIMEOUT;
+        let inner_weak = Arc::downgrade(self);
+        let timer = self.watcher.add_timeout(RECORDING_CLOSE_POLL, move || {
+            let Some(inner) = Weak::upgrade(&inner_weak) else {
+                return glib::ControlFlow::Break;
+            };
+            if !inner.finishing_branches.lock().unwrap().is_empty() && Instant::now() < deadline {
+                return glib::ControlFlow::Continue;
+            }
+            inner.reconnect_timer.lock().unwrap().take();
+            inner.schedule_retry(attempt);
+            glib::ControlFlow::Break
+        });
+        *self.reconnect_timer.lock().unwrap() = Some(timer);
+    }
+
+    /// Tears the source down and brings it back after the backoff of
+    /// `attempt`, or gives up.
+    fn schedule_retry(self: &Arc<Self>, attempt: u32) {
+        let Some(policy) = self.config.reconnect.as_ref() else {
+            return;
+        };
+        let _ = self.pipeline.set_state(gst::State::Null);
+        self.remove_source();
+        self.drop_finishing_branches();
+        if policy.max_attempts.is_some_and(|max| attempt > max) {
+            let attempts = attempt - 1;
+            warn!(
+                "Giving up on {} after {} attempts",
+                self.display_uri, attempts
+            );
+            self.reconnect_attempt.store(0, Ordering::Relaxed);
+            self.give_up(PlayerError::ReconnectFailed { attempts });
+            return;
+        }
+
+        let backoff = policy.backoff(attempt);
+        info!(
+            "Reconnecting to {} in {:?} (attempt {})",
+            self.display_uri, backoff, attempt
+        );
+        self.emit(PlayerEvent::Reconnecting { attempt });
+        let inner_weak = Arc::downgrade(self);
+        let timer = self.watcher.add_timeout(backoff, move || {
+            if let Some(inner) = Weak::upgrade(&inner_weak) {
+                inner.reconnect_timer.lock().unwrap().take();
+                inner.retry_source();
+            }
+            glib::ControlFlow::Break
+        });
+        *self.reconnect_timer.lock().unwrap() = Some(timer);
+    }
+
+    /// Brings the source back once the backoff of `reconnect` is over.
+    fn retry_source(self: &Arc<Self>) {
+        // `stop` may have been called while waiting.
+        let target_state = *self.target_state.lock().unwrap();
+        let restarted = self.add_source().and_then(|_| {
+            self.pipeline.set_state(target_state)?;
+            Ok(())
+        });
+        match restarted {
+            Ok(()) if target_state == gst::State::Null => {
+                self.reconnect_attempt.store(0, Ordering::Relaxed);
+            }
+            Ok(()) => (),
+            Err(err) => {
+                warn!(
+                    "Reconnect attempt {} failed: {}",
+                    self.reconnect_attempt.load(Ordering::Relaxed),
+                    err
+                );
+                self.reconnect();
+            }
+        }
+    }
+
+    /// Stops for good after `err`, leaving a fresh source behind so a
+    /// later `play` starts over.
+    fn give_up(self: &Arc<Self>, err: PlayerError) {
+        if let Err(err) = self.set_target_state(gst::State::Null) {
+            warn!("Failed to stop {}: {}", self.display_uri, err);
+        }
+        self.remove_source();
+        if let Err(err) = self.add_source() {
+            error!(
+                "Failed to rebuild the source of {}: {}",
+                self.display_uri, err
+            );
+        }
+        self.emit(PlayerEvent::Error(Arc::new(err)));
+    }
+
+    fn reselect_tracks(self: &Arc<Self>) -> Result<()> {
+        if *self.target_state.lock().unwrap() == gst::State::Null {
+            // Applied on the next start.
+            return Ok(());
+        }
+        self.restart_source()
+    }
+
+    /// Asks the credentials provider for a replacement after a 401. Returns
+    /// whether there is something new worth retrying with.
+    fn refresh_credentials(&self) -> bool {
+        let Some(provider) = self.credentials_provider.lock().unwrap().clone() else {
+            return false;
+        };
+        let rejected = self.credentials.lock().unwrap().clone();
+        match provider(&self.display_uri, rejected.as_ref()) {
+            Some(credentials) if Some(&credentials) != rejected.as_ref() => {
+                *self.credentials.lock().unwrap() = Some(credentials);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn link_source_pad(self: &Arc<Self>, source: &gst::Element, pad: &gst::Pad) -> Result<()> {
+        let media_type = media_type_of(pad)?;
+        if self.config.frame_timestamps {
+            add_onvif_probe(pad);
+        }
+        if self.is_pending_source(source) {
+            return self.link_pending_pad(source, pad, &media_type);
+        }
+        match media_type.as_str() {
+            "video" => self.link_video_pad(source, pad),
+            "audio" if self.config.audio => {
+                self.link_audio_pad_or_discard(source, pad);
+                Ok(())
+            }
+            _ => {
+                debug!("Ignoring pad with media type: {}", media_type);
+                Ok(())
+            }
+        }
+    }
+
+    fn link_video_pad(&self, source: &gst::Element, pad: &gst::Pad) -> Result<()> {
+        let sink_pad = self.video_sink.static_pad("sink").unwrap();
+        if sink_pad.is_linked() {
+            debug!("Video branch already linked, ignoring {}", pad.name());
+            return Ok(());
+        }
+
+        if let (Some(glimagesink), Some(texture)) = (&self.glimagesink, &self.texture) {
+            // Initialize GL display
+            let gl_display = gst_gl::GLDisplay::default();
+
+            // Set up the GL context
+            let gl_context = unsafe {
+                gst_gl::GLContext::new_wrapped(
+                    &gl_display,
+                    texture.ptr,
+                    gst_gl::GLPlatform::EGL,
+                    gst_gl::GLAPI::GLES2,
+                )
+            }
+            .ok_or(PlayerError::GlContextUnavailable)?;
+            gl_context.activate(true)?;
+            glimagesink.set_property("context", &gl_context);
+        }
+
+        let encoding = encoding_name_of(pad)?;
+        info!("Building {} video branch for {}", encoding, pad.name());
+        let (chain, tee) = self.build_video_chain(&encoding)?;
+        self.source_elements
+            .lock()
+            .unwrap()
+            .extend(chain.iter().cloned());
+        let video_sink = self.video_sink.clone();
+        on_decoder_src_pad(
+            chain.last().unwrap(),
+            self.config.decoder,
+            move |decoder, src_pad| {
+                let sink_pad = video_sink.static_pad("sink").unwrap();
+                if sink_pad.is_linked() {
+                    return;
+                }
+                if src_pad.link(&sink_pad).is_err() {
+                    post_error(decoder, PlayerError::link_failed(decoder, &video_sink));
+                }
+            },
+        );
+        for element in &chain {
+            element.sync_state_with_parent()?;
+        }
+        self.attach_tee_branches(&encoding, tee)?;
+
+        let queue = &chain[0];
+        pad.link(&queue.static_pad("sink").unwrap())
+            .map_err(|_| PlayerError::link_failed(source, queue))?;
+        Ok(())
+    }
+
+    /// Builds, adds and links `queue ! depay ! [parse] ! tee ! queue !
+    /// decode`, returning it along with the tee.
+    fn build_video_chain(&self, encoding: &str) -> Result<(Vec<gst::Element>, gst::Element)> {
+        let mut chain = vec![make_gs_element("queue")?];
+        chain.extend(make_decode_chain(encoding, self.config.decoder)?);
+        // Split the parsed stream off before decoding, for recording.
+        let tee = make_gs_element("tee")?;
+        tee.set_property("allow-not-linked", true);
+        let decoder_index = chain.len() - 1;
+        chain.insert(decoder_index, make_gs_element("queue")?);
+        chain.insert(decoder_index, tee.clone());
+        self.pipeline.add_many(&chain)?;
+        let refs: Vec<&gst::Element> = chain.iter().collect();
+        link_many(&refs)?;
+        Ok((chain, tee))
+    }
+
+    /// Hangs the pre-event buffer and the recording off the tee of the
+    /// playing video branch.
+    fn attach_tee_branches(&self, encoding: &str, tee: gst::Element) -> Result<()> {
+        let frames_received = self.frames_received.clone();
+        tee.static_pad("sink")
+            .unwrap()
+            .add_probe(gst::PadProbeType::BUFFER, move |_, _| {
+                frames_received.fetch_add(1, Ordering::Relaxed);
+                gst::PadProbeReturn::Ok
+            });
+        let encode_type = VideoEncodeType::from_encoding_name(encoding);
+        if let Some(prebuffer) = &self.prebuffer {
+            if matches!(
+                encode_type,
+                Some(VideoEncodeType::H264 | VideoEncodeType::H265)
+            ) {
+                self.attach_prebuffer(prebuffer, &tee)?;
+            }
+        }
+        if encode_type.is_none() {
+            // `decodebin3` depayloads it, the tee only sees RTP.
+            warn!("Can't record {} video, it isn't parsed", encoding);
+            return Ok(());
+        }
+        *self.recording_tee.lock().unwrap() = Some(tee);
+        self.attach_recording()
+    }
+
+    fn is_pending_source(&self, source: &gst::Element) -> bool {
+        self.pending_switch
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|pending| pending.source == *source)
+    }
+
+    fn is_from_pending(&self, src: Option<&gst::Object>) -> bool {
+        let pending_switch = self.pending_switch.lock().unwrap();
+        src.zip(pending_switch.as_ref())
+            .is_some_and(|(src, pending)| pending.contains(src))
+    }
+
+    /// Picks the stream variant for the render size and switches to it.
+    fn update_variant(self: &Arc<Self>) -> Result<()> {
+        let Some((width, height)) = *self.render_size.lock().unwrap() else {
+            return Ok(());
+        };
+        let active = *self.active_variant.lock().unwrap();
+        let pending = self
+            .pending_switch
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|pending| pending.variant);
+        let picked = pick_variant(
+            &self.variants.lock().unwrap(),
+            pending.or(active),
+            width,
+            height,
+        );
+        match picked {
+            Some(index) if Some(index) == active => {
+                self.cancel_switch();
+                Ok(())
+            }
+            Some(index) if Some(index) != pending => self.switch_variant(index),
+            _ => Ok(()),
+        }
+    }
+
+    /// Starts pre-rolling variant `index` next to the playing source, see
+    /// `finish_switch`.
+    fn switch_variant(self: &Arc<Self>, index: usize) -> Result<()> {
+        let Some(uri) = self
+            .variants
+            .lock()
+            .unwrap()
+            .get(index)
+            .map(|variant| variant.uri.clone())
+        else {
+            return Ok(());
+        };
+        self.cancel_switch();
+        if *self.target_state.lock().unwrap() == gst::State::Null {
+            // Nothing is showing, so there is nothing to keep on screen.
+            *self.active_variant.lock().unwrap() = Some(index);
+            self.remove_source();
+            return self.add_source();
+        }
+
+        info!(
+            "Pre-rolling stream variant {} of {}",
+            index, self.display_uri
+        );
+        let source = self.make_source(&uri)?;
+        self.pipeline.add(&source)?;
+        *self.pending_switch.lock().unwrap() = Some(PendingSwitch::new(index, source.clone()));
+        source.sync_state_with_parent()?;
+        Ok(())
+    }
+
+    fn cancel_switch(&self) {
+        if let Some(pending) = self.pending_switch.lock().unwrap().take() {
+            debug!("Dropping pending stream variant {}", pending.variant);
+            pending.cancel(&self.pipeline);
+        }
+    }
+
+    /// Builds the video branch of a pre-rolling variant, holding its first
+    /// frame decoded from a keyframe. Its audio pad is only linked once it
+    /// is swapped in.
+    fn link_pending_pad(
+        self: &Arc<Self>,
+        source: &gst::Element,
+        pad: &gst::Pad,
+        media_type: &str,
+    ) -> Result<()> {
+        let mut pending_switch = self.pending_switch.lock().unwrap();
+        let Some(pending) = pending_switch.as_mut() else {
+            return Ok(());
+        };
+        match media_type {
+            "video" if pending.tee.is_none() => (),
+            "audio" if self.config.audio && pending.audio_pad.is_none() => {
+                pending.audio_pad = Some(pad.clone());
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+
+        let encoding = encoding_name_of(pad)?;
+        let (chain, tee) = self.build_video_chain(&encoding)?;
+        if VideoEncodeType::from_encoding_name(&encoding).is_some() {
+            // Right behind the queue.
+            drop_until_keyframe(&chain[1]);
+        }
+        pending.elements.extend(chain.iter().cloned());
+        pending.encoding = Some(encoding);
+        pending.tee = Some(tee);
+        drop(pending_switch);
+
+        let inner_weak = Arc::downgrade(self);
+        let pending_source = source.clone();
+        on_decoder_src_pad(
+            chain.last().unwrap(),
+            self.config.decoder,
+            move |_, src_pad| {
+                let Some(inner) = Weak::upgrade(&inner_weak) else {
+                    return;
+                };
+                let mut pending_switch = inner.pending_switch.lock().unwrap();
+                let Some(pending) = pending_switch
+                    .as_mut()
+                    .filter(|pending| pending.source == pending_source)
+                else {
+                    return;
+                };
+                let probe = src_pad.add_probe(
+                    gst::PadProbeType::BLOCK | gst::PadProbeType::BUFFER,
+                    |pad, _| {
+                        if let Some(decoder) = pad.parent_element() {
+                            let _ = decoder.post_message(gst::message::Application::new(
+                                gst::Structure::new_empty(VARIANT_READY),
+                            ));
+                        }
+                        gst::PadProbeReturn::Ok
+                    },
+                );
+                pending.ready_probe = probe.map(|probe| (src_pad.clone(), probe));
+            },
+        );
+        for element in &chain {
+            element.sync_state_with_parent()?;
+        }
+        let queue = &chain[0];
+        pad.link(&queue.static_pad("sink").unwrap())
+            .map_err(|_| PlayerError::link_failed(source, queue))?;
+        Ok(())
+    }
+
+    /// Swaps a pre-rolled variant in for the playing source, once it has
+    /// decoded a frame. Runs on the bus thread.
+    fn finish_switch(&self) -> Result<()> {
+        let pending = {
+            let mut pending_switch = self.pending_switch.lock().unwrap();
+            if pending_switch
+                .as_ref()
+                .is_none_or(|pending| pending.ready_probe.is_none())
+            {
+                return Ok(());
+            }
+            pending_switch.take().unwrap()
+        };
+        let (new_src, probe) = pending.ready_probe.unwrap();
+
+        let sink_pad = self.video_sink.static_pad("sink").unwrap();
+        if let Some(old_src) = sink_pad.peer() {
+            // Frames the old branch still decodes go nowhere; unlinked it
+            // would fail with not-linked.
+            old_src.add_probe(gst::PadProbeType::DATA_DOWNSTREAM, |_, _| {
+                gst::PadProbeReturn::Drop
+            });
+            let _ = old_src.unlink(&sink_pad);
+        }
+        let linked = new_src.link(&sink_pad);
+        new_src.remove_probe(probe);
+        linked.map_err(|_| PlayerError::link_failed(&new_src, &self.video_sink))?;
+
+        self.remove_source();
+        *self.source_elements.lock().unwrap() = pending.elements;
+        *self.active_variant.lock().unwrap() = Some(pending.variant);
+        if let Some(tracks) = pending.tracks {
+            self.state.send_modify(|state| state.tracks = tracks);
+        }
+        info!(
+            "Switched {} to stream variant {}",
+            self.display_uri, pending.variant
+        );
+        if let (Some(encoding), Some(tee)) = (pending.encoding, pending.tee) {
+            self.attach_tee_branches(&encoding, tee)?;
+        }
+        if let Some(audio_pad) = pending.audio_pad {
+            self.link_audio_pad_or_discard(&pending.source, &audio_pad);
+        }
+        self.emit(PlayerEvent::VariantSwitched {
+            index: pending.variant,
+        });
+        Ok(())
+    }
+
+    /// Links an audio pad, or a `fakesink` if that fails: missing audio is
+    /// no reason to stop the video.
+    fn link_audio_pad_or_discard(&self, source: &gst::Element, pad: &gst::Pad) {
+        let Err(err) = self.link_audio_pad(source, pad) else {
+            return;
+        };
+        warn!("Playing {} without audio: {}", self.display_uri, err);
+        if let Err(err) = self.discard_pad(pad) {
+            warn!("Failed to discard {}: {}", pad.name(), err);
+        }
+    }
+
+    /// Builds `queue ! depay ! [parse] ! decode ! audioconvert !
+    /// audioresample ! volume ! sink` for the first audio pad. Nothing is
+    /// left in the pipeline if it fails.
+    fn link_audio_pad(&self, source: &gst::Element, pad: &gst::Pad) -> Result<()> {
+        if self.audio_volume.lock().unwrap().is_some() {
+            debug!("Audio branch already linked, ignoring {}", pad.name());
+            return Ok(());
+        }
+
+        let encoding = encoding_name_of(pad)?;
+        info!("Building {} audio branch for {}", encoding, pad.name());
+        let (volume_level, muted) = {
+            let state = self.state.borrow();
+            (state.volume, state.muted)
+        };
+        let volume = make_gs_element("volume")?;
+        volume.set_property("volume", volume_level / 100.0);
+        volume.set_property("mute", muted);
+        let sink = make_audio_sink(self.audio_output.lock().unwrap().as_ref())?;
+
+        let mut chain = vec![make_gs_element("queue")?];
+        chain.extend(make_audio_decode_chain(&encoding)?);
+        chain.push(volume.clone());
+        chain.push(sink.clone());
+        self.pipeline.add_many(&chain)?;
+        let linked = link_many(&chain.iter().collect::<Vec<_>>()).and_then(|_| {
+            for element in &chain {
+                element.sync_state_with_parent()?;
+            }
+            let queue = &chain[0];
+            pad.link(&queue.static_pad("sink").unwrap())
+                .map_err(|_| PlayerError::link_failed(source, queue))?;
+            Ok(())
+        });
+        if let Err(err) = linked {
+            for element in &chain {
+                let _ = element.set_state(gst::State::Null);
+                let _ = self.pipeline.remove(element);
+            }
+            return Err(err);
+        }
+        self.source_elements
+            .lock()
+            .unwrap()
+            .extend(chain.iter().cloned());
+
+        let state = self.state.clone();
+        volume
+            .static_pad("sink")
+            .unwrap()
+            .connect_notify(Some("caps"), move |pad, _| {
+                if let Some(params) = pad
+                    .current_caps()
+                    .and_then(|caps| AudioParams::from_caps(&caps))
+                {
+                    state.send_modify(|state| state.audio_params = params);
+                }
+            });
+        *self.audio_volume.lock().unwrap() = Some(volume);
+        *self.audio_sink.lock().unwrap() = Some(sink);
+        Ok(())
+    }
+
+    /// Links `pad` to a `fakesink`, so its stream doesn't stall `rtspsrc`.
+    fn discard_pad(&self, pad: &gst::Pad) -> Result<()> {
+        let sink = gst::ElementFactory::make("fakesink")
+            .property("sync", false)
+            .property("async", false)
+            .build()
+            .map_err(|_| PlayerError::ElementMissing {
+                factory: "fakesink".to_string(),
+            })?;
+        self.pipeline.add(&sink)?;
+        self.source_elements.lock().unwrap().push(sink.clone());
+        sink.sync_state_with_parent()?;
+        pad.link(&sink.static_pad("sink").unwrap())
+            .map_err(|_| PlayerError::link_failed(pad, &sink))?;
+        Ok(())
+    }
+
+    /// Replaces the audio sink with one for `audio_output` once no buffer
+    /// is passing through.
+    fn swap_audio_sink(&self) -> Result<()> {
+        let Some(volume) = self.audio_volume.lock().unwrap().clone() else {
+            return Ok(());
+        };
+        let new_sink = make_audio_sink(self.audio_output.lock().unwrap().as_ref())?;
+        let old_sink = self.audio_sink.lock().unwrap().replace(new_sink.clone());
+        {
+            let mut source_elements = self.source_elements.lock().unwrap();
+            source_elements.retain(|element| Some(element) != old_sink.as_ref());
+            source_elements.push(new_sink.clone());
+        }
+
+        let pipeline = self.pipeline.clone();
+        volume
+            .static_pad("src")
+            .unwrap()
+            .add_probe(gst::PadProbeType::IDLE, move |_, _| {
+                if let Some(old_sink) = &old_sink {
+                    volume.unlink(old_sink);
+                    let _ = old_sink.set_state(gst::State::Null);
+                    let _ = pipeline.remove(old_sink);
+                }
+                let swapped = pipeline
+                    .add(&new_sink)
+                    .map_err(PlayerError::from)
+                    .and_then(|_| link_many(&[&volume, &new_sink]))
+                    .and_then(|_| Ok(new_sink.sync_state_with_parent()?));
+                if let Err(err) = swapped {
+                    warn!("Failed to switch the audio output: {}", err);
+                }
+                gst::PadProbeReturn::Remove
+            });
+        Ok(())
+    }
+}
+
+/// Calls `f` with the decoder's source pad, right away or, for
+/// `decodebin3`, once it has picked a decoder.
+fn on_decoder_src_pad(
+    decoder: &gst::Element,
+    mode: DecoderMode,
+    f: impl Fn(&gst::Element, &gst::Pad) + Send + Sync + 'static,
+) {
+    match mode {
+        DecoderMode::Manual => f(decoder, &decoder.static_pad("src").unwrap()),
+        DecoderMode::DecodeBin3 => {
+            decoder.connect_pad_added(move |decodebin, src_pad| f(decodebin, src_pad));
+        }
+    }
+}
+
+/// Handles a message of the player's bus, on the watcher thread.
+fn handle_message(inner: &Arc<PlayerInner>, msg: &gst::Message) {
+    use gst::MessageView;
+
+    let lost = match msg.view() {
+        MessageView::Eos(..) => {
+            info!("End of stream");
+            inner.state.send_modify(|state| state.completed = true);
+            inner.emit(PlayerEvent::Eos);
+            true
+        }
+        MessageView::Error(..) if inner.is_removed(msg.src()) => {
+            debug!("Ignoring error of an element no longer in the pipeline");
+            false
+        }
+        MessageView::Error(err) if inner.is_from_pending(msg.src()) => {
+            warn!(
+                "Stream variant failed, staying on the current one: {}",
+                PlayerError::from_message(err)
+            );
+            inner.cancel_switch();
+            false
+        }
+        MessageView::Application(app)
+            if app.structure().is_some_and(|s| s.has_name(VARIANT_READY)) =>
+        {
+            if let Err(err) = inner.finish_switch() {
+                error!("Failed to switch stream variant: {}", err);
+                inner.emit(PlayerEvent::Error(Arc::new(err)));
+                true
+            } else {
+                false
+            }
+        }
+        MessageView::Error(err) => {
+            let err = PlayerError::from_message(err);
+            error!("{}", err);
+            if matches!(err, PlayerError::Unauthorized) && inner.refresh_credentials() {
+                info!("Retrying {} with new credentials", inner.display_uri);
+                if let Err(err) = inner.restart_source() {
+                    error!("Failed to restart {}: {}", inner.display_uri, err);
+                }
+                return;
+            }
+            if !err.is_recoverable() {
+                inner.give_up(err);
+                return;
+            }
+            inner.emit(PlayerEvent::Error(Arc::new(err)));
+            true
+        }
+        MessageView::StreamStart(..) => {
+            let attempt = inner.reconnect_attempt.swap(0, Ordering::Relaxed);
+            if attempt > 0 {
+                info!(
+                    "Reconnected to {} after {} attempts",
+                    inner.display_uri, attempt
+                );
+                inner.emit(PlayerEvent::Reconnected);
+            }
+            false
+        }
+        MessageView::StateChanged(s) => {
+            debug!(
+                "State changed from {:?}: {:?} -> {:?} ({:?})",
+                s.src().map(|s| s.path_string()),
+                s.old(),
+                s.current(),
+                s.pending()
+            );
+            if s.src() == Some(inner.pipeline.upcast_ref::<gst::Object>()) {
+                let playing = s.current() == gst::State::Playing;
+                inner.state.send_modify(|state| {
+                    state.playing = playing;
+                    state.completed &= !playing;
+                });
+                inner.emit(PlayerEvent::StateChanged(s.current()));
+            }
+            false
+        }
+        MessageView::Buffering(buffering) => {
+            let percent = buffering.percent();
+            inner.state.send_modify(|state| {
+                state.buffering = percent < 100;
+                state.buffering_percentage = percent as f64;
+            });
+            false
+        }
+        MessageView::Latency(..) => {
+            let _ = inner.pipeline.recalculate_latency();
+            let mut query = gst::query::Latency::new();
+            if inner.pipeline.query(&mut query) {
+                let (_live, min, _max) = query.result();
+                let latency = Duration::from_nanos(min.nseconds());
+                inner.state.send_modify(|state| state.latency = latency);
+            }
+            false
+        }
+        MessageView::Element(element) => {
+            let Some(s) = element.structure() else {
+                return;
+            };
+            let (Ok(location), Ok(running_time)) = (
+                s.get::<String>("location"),
+                s.get::<gst::ClockTime>("running-time"),
+            ) else {
+                return;
+            };
+            let mut fragment_starts = inner.fragment_starts.lock().unwrap();
+            if s.has_name(FRAGMENT_OPENED) {
+                fragment_starts.insert(location, running_time);
+            } else if s.has_name(FRAGMENT_CLOSED) {
+                let start = fragment_starts
+                    .remove(&location)
+                    .unwrap_or(gst::ClockTime::ZERO);
+                drop(fragment_starts);
+                let duration = Duration::from_nanos(running_time.saturating_sub(start).nseconds());
+                inner.on_fragment_closed(msg.src(), PathBuf::from(location), duration);
+            }
+            false
+        }
+        MessageView::Qos(qos) if inner.is_video_sink(msg.src()) => {
+            let (_format, processed, dropped) = qos.stats();
+            inner.state.send_modify(|state| {
+                state.frames_rendered = processed.value().max(0) as u64;
+                state.frames_dropped = dropped.value().max(0) as u64;
+            });
+            false
+        }
+        _ => false,
+    };
+
+    if lost {
+        inner.reconnect();
+    }
+}
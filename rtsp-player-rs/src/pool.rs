@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::config::{PlayerConfig, VideoSinkMode};
+use crate::error::{PlayerError, Result};
+use crate::gl_texture::GlFrame;
+use crate::player::{RtspPlayer, SinkSetup};
+use crate::watcher::BusWatcher;
+use crate::RenderUnix;
+
+/// Identifies a player within a `PlayerPool`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlayerId(u64);
+
+/// Caps how many players may decode at once. A player takes a slot when it
+/// leaves `gst::State::Null` and gives it back when stopped or dropped.
+#[derive(Debug)]
+pub(crate) struct DecoderBudget {
+    max: usize,
+    used: Mutex<usize>,
+}
+
+/// A slot of a `DecoderBudget`, released on drop.
+#[derive(Debug)]
+pub(crate) struct DecoderPermit {
+    budget: Arc<DecoderBudget>,
+}
+
+impl DecoderBudget {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            used: Mutex::new(0),
+        }
+    }
+
+    pub(crate) fn try_acquire(self: &Arc<Self>) -> Result<DecoderPermit> {
+        let mut used = self.used.lock().unwrap();
+        if *used >= self.max {
+            return Err(PlayerError::DecoderBudgetExhausted { max: self.max });
+        }
+        *used += 1;
+        Ok(DecoderPermit {
+            budget: self.clone(),
+        })
+    }
+
+    fn in_use(&self) -> usize {
+        *self.used.lock().unwrap()
+    }
+}
+
+impl Drop for DecoderPermit {
+    fn drop(&mut self) {
+        *self.budget.used.lock().unwrap() -= 1;
+    }
+}
+
+/// Totals over the players of a `PlayerPool`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolState {
+    pub players: usize,
+    pub playing: usize,
+    pub buffering: usize,
+    /// Players whose server ended the stream.
+    pub completed: usize,
+    pub decoders_in_use: usize,
+    pub max_decoders: usize,
+    pub frames_rendered: u64,
+    pub frames_dropped: u64,
+}
+
+/// Players for a wall of cameras, sharing one GL display, one bus thread
+/// and a decoder budget.
+///
+/// Every player renders through the pool's `RenderUnix`, whose GL context
+/// (and GL thread) sits on the shared display, so the pipelines all upload
+/// into it instead of creating one per camera. Their buses and timers are
+/// all served by the same watcher thread.
+pub struct PlayerPool {
+    render: Arc<RenderUnix>,
+    budget: Arc<DecoderBudget>,
+    watcher: Arc<BusWatcher>,
+    players: Mutex<BTreeMap<PlayerId, Arc<RtspPlayer>>>,
+    next_id: AtomicU64,
+}
+
+impl PlayerPool {
+    /// At most `max_decoders` players can be playing or paused at once;
+    /// `play` on any further one fails with
+    /// `PlayerError::DecoderBudgetExhausted`.
+    pub fn new(render: Arc<RenderUnix>, max_decoders: usize) -> Self {
+        Self {
+            render,
+            budget: Arc::new(DecoderBudget::new(max_decoders)),
+            watcher: Arc::new(BusWatcher::new("rtsp-player-pool-bus")),
+            players: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    pub fn render(&self) -> &Arc<RenderUnix> {
+        &self.render
+    }
+
+    /// Adds a player in `VideoSinkMode::GlTexture` on the shared display,
+    /// see `RtspPlayer::with_gl_render`. It starts stopped.
+    pub fn add(
+        &self,
+        uri: String,
+        config: PlayerConfig,
+        on_frame: impl Fn(GlFrame) + Send + Sync + 'static,
+    ) -> Result<PlayerId> {
+        let config = PlayerConfig {
+            video_sink: VideoSinkMode::GlTexture,
+            ..config
+        };
+        let sink_setup = SinkSetup::GlRender(self.render.clone(), Box::new(on_frame));
+        let player = RtspPlayer::build(
+            uri,
+            config,
+            sink_setup,
+            Some(self.budget.clone()),
+            Some(self.watcher.clone()),
+        )?;
+        Ok(self.insert(player))
+    }
+
+    /// Adds a player ending in `sink`, see `RtspPlayer::with_video_sink`.
+    /// It counts against the decoder budget but renders on its own.
+    pub fn add_with_sink(
+        &self,
+        uri: String,
+        config: PlayerConfig,
+        sink: gst::Element,
+    ) -> Result<PlayerId> {
+        let player = RtspPlayer::build(
+            uri,
+            config,
+            SinkSetup::Element(sink),
+            Some(self.budget.clone()),
+            Some(self.watcher.clone()),
+        )?;
+        Ok(self.insert(player))
+    }
+
+    fn insert(&self, player: RtspPlayer) -> PlayerId {
+        let id = PlayerId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.players.lock().unwrap().insert(id, Arc::new(player));
+        id
+    }
+
+    pub fn player(&self, id: PlayerId) -> Option<Arc<RtspPlayer>> {
+        self.players.lock().unwrap().get(&id).cloned()
+    }
+
+    pub fn ids(&self) -> Vec<PlayerId> {
+        self.players.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Takes the player out of the pool. It shuts down once the last
+    /// reference to it is dropped.
+    pub fn remove(&self, id: PlayerId) -> Option<Arc<RtspPlayer>> {
+        self.players.lock().unwrap().remove(&id)
+    }
+
+    /// Starts players in id order until the decoder budget runs out.
+    /// Returns the ones that couldn't be started.
+    pub fn play_all(&self) -> Vec<(PlayerId, PlayerError)> {
+        self.players
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(id, player)| player.play().err().map(|err| (*id, err)))
+            .collect()
+    }
+
+    pub fn stop_all(&self) -> Result<()> {
+        for player in self.players.lock().unwrap().values() {
+            player.stop()?;
+        }
+        Ok(())
+    }
+
+    pub fn state(&self) -> PoolState {
+        let players = self.players.lock().unwrap();
+        let mut pool_state = PoolState {
+            players: players.len(),
+            decoders_in_use: self.budget.in_use(),
+            max_decoders: self.budget.max,
+            ..Default::default()
+        };
+        for player in players.values() {
+            let state = player.watch_state();
+            let state = state.borrow();
+            pool_state.playing += state.playing as usize;
+            pool_state.buffering += state.buffering as usize;
+            pool_state.completed += state.completed as usize;
+            pool_state.frames_rendered += state.frames_rendered;
+            pool_state.frames_dropped += state.frames_dropped;
+        }
+        pool_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permits_return_to_the_budget() {
+        let budget = Arc::new(DecoderBudget::new(2));
+        let first = budget.try_acquire().unwrap();
+        let _second = budget.try_acquire().unwrap();
+        assert!(matches!(
+            budget.try_acquire(),
+            Err(PlayerError::DecoderBudgetExhausted { max: 2 })
+        ));
+
+        drop(first);
+        assert_eq!(budget.in_use(), 1);
+        assert!(budget.try_acquire().is_ok());
+    }
+}
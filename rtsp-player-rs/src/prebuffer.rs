@@ -0,0 +1,409 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use gst::prelude::*;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::error::{PlayerError, Result};
+use crate::events::PlayerEvent;
+use crate::utils::{link_many, make_gs_element, make_named_element};
+
+/// Name of the `appsink` filling the pre-event buffer.
+pub(crate) const PREBUFFER_APPSINK_NAME: &str = "prebuffer-appsink";
+/// How long past its `post` a clip waits for the stream to get there before
+/// it is closed anyway.
+pub(crate) const CLIP_STALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How much of the encoded stream `RtspPlayer` keeps in memory for
+/// `RtspPlayer::save_clip`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrebufferConfig {
+    /// Keeps this much of the stream, plus the rest of the oldest GOP so the
+    /// buffer always starts on a keyframe.
+    pub max_duration: Duration,
+    /// Drops whole GOPs, oldest first, beyond this many bytes. The newest
+    /// GOP is always kept.
+    pub max_bytes: u64,
+}
+
+impl Default for PrebufferConfig {
+    fn default() -> Self {
+        Self {
+            max_duration: Duration::from_secs(30),
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Rolling buffer of parsed H.264/H.265 access units, fed from the tee in
+/// front of the decoder.
+pub(crate) struct Prebuffer {
+    config: PrebufferConfig,
+    ring: Mutex<Ring>,
+    events: broadcast::Sender<PlayerEvent>,
+}
+
+impl Prebuffer {
+    pub(crate) fn new(config: PrebufferConfig, events: broadcast::Sender<PlayerEvent>) -> Self {
+        Self {
+            config,
+            ring: Mutex::new(Ring::default()),
+            events,
+        }
+    }
+
+    /// Builds `queue ! appsink` to hang off the tee in front of the
+    /// decoder.
+    pub(crate) fn make_branch(self: &Arc<Self>) -> Result<[gst::Element; 2]> {
+        let queue = make_gs_element("queue")?;
+        let appsink = gst_app::AppSink::builder()
+            .name(PREBUFFER_APPSINK_NAME)
+            .sync(false)
+            .build();
+        let prebuffer = self.clone();
+        appsink.set_callbacks(
+            gst_app::AppSinkCallbacks::builder()
+                .new_sample(move |appsink| {
+                    let sample = appsink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                    if let (Some(caps), Some(buffer)) = (sample.caps(), sample.buffer_owned()) {
+                        prebuffer.push(caps, buffer);
+                    }
+                    Ok(gst::FlowSuccess::Ok)
+                })
+                .build(),
+        );
+        Ok([queue, appsink.upcast()])
+    }
+
+    fn push(&self, caps: &gst::CapsRef, buffer: gst::Buffer) {
+        let mut ring = self.ring.lock().unwrap();
+        let Some(timestamp) = buffer.dts_or_pts() else {
+            return;
+        };
+
+        // Dropping a clip finishes it.
+        ring.clips
+            .retain_mut(|clip| timestamp <= clip.deadline && clip.push(&buffer));
+        ring.push(caps, buffer, &self.config);
+    }
+
+    /// Writes the buffered stream from the last keyframe at least `pre`
+    /// before the newest frame, and the stream for `post` after it, to an
+    /// MP4 at `path`. `PlayerEvent::FileClosed` follows once it has been
+    /// written.
+    pub(crate) fn save_clip(&self, pre: Duration, post: Duration, path: PathBuf) -> Result<()> {
+        // Only the range is picked under the lock; starting the clip and
+        // pushing the pre-roll would hold up the streaming thread.
+        let (caps, newest, preroll) = {
+            let ring = self.ring.lock().unwrap();
+            let (Some(caps), Some(newest)) = (
+                ring.caps.clone(),
+                ring.buffers.back().and_then(|buffer| buffer.dts_or_pts()),
+            ) else {
+                return Err(recording_error("Nothing has been buffered yet"));
+            };
+            let from = newest.saturating_sub(clock_time(pre));
+            let start = ring
+                .buffers
+                .iter()
+                .rposition(|buffer| {
+                    is_keyframe(buffer) && buffer.dts_or_pts().is_some_and(|ts| ts <= from)
+                })
+                .unwrap_or(0);
+            let preroll = ring.buffers.range(start..).cloned().collect::<Vec<_>>();
+            (caps, newest, preroll)
+        };
+        let parser = match caps.structure(0).map(|s| s.name().as_str()) {
+            Some("video/x-h264") => "h264parse",
+            Some("video/x-h265") => "h265parse",
+            _ => return Err(recording_error("Clips can only be saved from H.264/H.265")),
+        };
+        let base = preroll[0].dts_or_pts().unwrap_or(newest);
+
+        let mut clip = ClipWriter::new(
+            path,
+            &caps,
+            parser,
+            base,
+            newest + clock_time(post),
+            self.events.clone(),
+        )?;
+        for buffer in &preroll {
+            clip.push(buffer);
+        }
+
+        let mut ring = self.ring.lock().unwrap();
+        if ring.caps.as_ref() != Some(&caps) {
+            // The source changed meanwhile, the clip ends with the pre-roll.
+            return Ok(());
+        }
+        // Catch up with what arrived while the lock was released.
+        let missed = ring
+            .buffers
+            .iter()
+            .filter(|buffer| buffer.dts_or_pts() > Some(newest));
+        for buffer in missed {
+            if buffer.dts_or_pts() > Some(clip.deadline) || !clip.push(buffer) {
+                return Ok(());
+            }
+        }
+        ring.clips.push(clip);
+        Ok(())
+    }
+
+    /// Closes the clip being written to `path`, if it is still waiting for
+    /// the stream to reach its end.
+    pub(crate) fn close_clip(&self, path: &Path) {
+        self.ring.lock().unwrap().clips.retain(|clip| {
+            if clip.path != path {
+                return true;
+            }
+            warn!("Stream stalled, closing {} early", path.display());
+            false
+        });
+    }
+
+    /// Forgets the buffered stream and closes clips still being written,
+    /// as timestamps start over with a new source.
+    pub(crate) fn reset(&self) {
+        let mut ring = self.ring.lock().unwrap();
+        ring.clips.clear();
+        ring.clear();
+    }
+}
+
+#[derive(Default)]
+struct Ring {
+    caps: Option<gst::Caps>,
+    /// Starts on a keyframe, in decode order.
+    buffers: VecDeque<gst::Buffer>,
+    bytes: u64,
+    clips: Vec<ClipWriter>,
+}
+
+impl Ring {
+    fn push(&mut self, caps: &gst::CapsRef, buffer: gst::Buffer, config: &PrebufferConfig) {
+        if self.caps.as_deref() != Some(caps) {
+            // Buffered frames can't be muxed with the new parameter sets.
+            self.clear();
+            self.caps = Some(caps.to_owned());
+        }
+        if self.buffers.is_empty() && !is_keyframe(&buffer) {
+            return;
+        }
+        self.bytes += buffer.size() as u64;
+        self.buffers.push_back(buffer);
+
+        let newest = self.buffers.back().and_then(|buffer| buffer.dts_or_pts());
+        // Drop the oldest GOP while the rest is still long enough, or while
+        // over the byte budget.
+        while let Some(next_gop) = self
+            .buffers
+            .iter()
+            .skip(1)
+            .position(is_keyframe)
+            .map(|position| position + 1)
+        {
+            let rest = newest
+                .zip(self.buffers[next_gop].dts_or_pts())
+                .map_or(gst::ClockTime::ZERO, |(newest, start)| {
+                    newest.saturating_sub(start)
+                });
+            if rest < clock_time(config.max_duration) && self.bytes <= config.max_bytes {
+                break;
+            }
+            for buffer in self.buffers.drain(..next_gop) {
+                self.bytes -= buffer.size() as u64;
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.caps = None;
+        self.buffers.clear();
+        self.bytes = 0;
+    }
+}
+
+/// `appsrc ! parse ! mp4mux ! filesink` writing one clip, with timestamps
+/// rebased to start at zero. Dropping it finishes the file.
+struct ClipWriter {
+    path: PathBuf,
+    pipeline: gst::Pipeline,
+    appsrc: gst_app::AppSrc,
+    base: gst::ClockTime,
+    /// The clip ends with the last buffer up to this.
+    deadline: gst::ClockTime,
+    end: gst::ClockTime,
+    events: broadcast::Sender<PlayerEvent>,
+}
+
+impl ClipWriter {
+    fn new(
+        path: PathBuf,
+        caps: &gst::Caps,
+        parser: &str,
+        base: gst::ClockTime,
+        deadline: gst::ClockTime,
+        events: broadcast::Sender<PlayerEvent>,
+    ) -> Result<Self> {
+        let pipeline = gst::Pipeline::with_name("clip");
+        let appsrc = gst_app::AppSrc::builder()
+            .caps(caps)
+            .format(gst::Format::Time)
+            .max_bytes(0)
+            .build();
+        let filesink = make_named_element("filesink", "clip-sink")?;
+        filesink.set_property("location", path.to_string_lossy().as_ref());
+        let elements = [
+            appsrc.upcast_ref::<gst::Element>().clone(),
+            make_gs_element(parser)?,
+            make_gs_element("mp4mux")?,
+            filesink,
+        ];
+        pipeline.add_many(&elements)?;
+        link_many(&elements.iter().collect::<Vec<_>>())?;
+        if let Err(err) = pipeline.set_state(gst::State::Playing) {
+            let _ = pipeline.set_state(gst::State::Null);
+            return Err(err.into());
+        }
+        info!("Saving clip to {}", path.display());
+        Ok(Self {
+            path,
+            pipeline,
+            appsrc,
+            base,
+            deadline,
+            end: base,
+            events,
+        })
+    }
+
+    /// Returns `false` once the clip's pipeline stopped taking buffers.
+    fn push(&mut self, buffer: &gst::Buffer) -> bool {
+        let mut buffer = buffer.copy();
+        {
+            let buffer = buffer.get_mut().unwrap();
+            buffer.set_pts(buffer.pts().map(|pts| pts.saturating_sub(self.base)));
+            buffer.set_dts(buffer.dts().map(|dts| dts.saturating_sub(self.base)));
+        }
+        if let Some(timestamp) = buffer.dts_or_pts() {
+            self.end = self
+                .end
+                .max(self.base + timestamp + buffer.duration().unwrap_or(gst::ClockTime::ZERO));
+        }
+        self.appsrc.push_buffer(buffer).is_ok()
+    }
+}
+
+impl Drop for ClipWriter {
+    /// Ends the clip and reports it once the muxer has written it out.
+    fn drop(&mut self) {
+        let _ = self.appsrc.end_of_stream();
+        let pipeline = self.pipeline.clone();
+        let path = self.path.clone();
+        let duration = Duration::from_nanos(self.end.saturating_sub(self.base).nseconds());
+        let events = self.events.clone();
+        let spawned = thread::Builder::new()
+            .name("rtsp-player-clip".to_string())
+            .spawn(move || {
+                let bus = pipeline.bus().unwrap();
+                let msg = bus.timed_pop_filtered(
+                    gst::ClockTime::NONE,
+                    &[gst::MessageType::Eos, gst::MessageType::Error],
+                );
+                let _ = pipeline.set_state(gst::State::Null);
+                let event = match msg.as_ref().map(|msg| msg.view()) {
+                    Some(gst::MessageView::Error(err)) => {
+                        warn!("Failed to save {}: {}", path.display(), err.error());
+                        PlayerEvent::Error(Arc::new(recording_error(&format!(
+                            "{}: {}",
+                            path.display(),
+                            err.error()
+                        ))))
+                    }
+                    _ => {
+                        info!("Saved clip {} ({:?})", path.display(), duration);
+                        PlayerEvent::FileClosed { path, duration }
+                    }
+                };
+                let _ = events.send(event);
+            });
+        if let Err(err) = spawned {
+            warn!("Failed to wait for {}: {}", self.path.display(), err);
+            let _ = self.pipeline.set_state(gst::State::Null);
+        }
+    }
+}
+
+fn is_keyframe(buffer: &gst::Buffer) -> bool {
+    !buffer.flags().contains(gst::BufferFlags::DELTA_UNIT)
+}
+
+fn clock_time(duration: Duration) -> gst::ClockTime {
+    gst::ClockTime::from_nseconds(duration.as_nanos() as u64)
+}
+
+fn recording_error(message: &str) -> PlayerError {
+    PlayerError::Recording {
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 100ms frames with a keyframe every second.
+    fn frame(index: u64) -> gst::Buffer {
+        let mut buffer = gst::Buffer::with_size(1000).unwrap();
+        {
+            let buffer = buffer.get_mut().unwrap();
+            buffer.set_dts(gst::ClockTime::from_mseconds(index * 100));
+            buffer.set_duration(gst::ClockTime::from_mseconds(100));
+            if index % 10 != 0 {
+                buffer.set_flags(gst::BufferFlags::DELTA_UNIT);
+            }
+        }
+        buffer
+    }
+
+    fn span(ring: &Ring) -> gst::ClockTime {
+        let first = ring.buffers.front().unwrap().dts().unwrap();
+        let last = ring.buffers.back().unwrap().dts().unwrap();
+        last - first
+    }
+
+    #[test]
+    fn ring_keeps_whole_gops() {
+        gst::init().unwrap();
+        let caps = gst::Caps::builder("video/x-h264").build();
+        let config = PrebufferConfig {
+            max_duration: Duration::from_secs(2),
+            max_bytes: u64::MAX,
+        };
+
+        let mut ring = Ring::default();
+        // Joining mid-GOP: the first frames can't be decoded on their own.
+        for index in 5..50 {
+            ring.push(&caps, frame(index), &config);
+        }
+        assert!(is_keyframe(ring.buffers.front().unwrap()));
+        assert_eq!(span(&ring), gst::ClockTime::from_mseconds(2900));
+        assert_eq!(ring.bytes, 30 * 1000);
+
+        let config = PrebufferConfig {
+            max_bytes: 5 * 1000,
+            ..config
+        };
+        ring.push(&caps, frame(50), &config);
+        assert_eq!(ring.buffers.len(), 1);
+        assert_eq!(ring.bytes, 1000);
+    }
+}
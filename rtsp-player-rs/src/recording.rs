@@ -0,0 +1,232 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use gst::prelude::*;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PlayerError, Result};
+use crate::utils::{link_many, make_gs_element};
+
+/// Element messages posted by `splitmuxsink` around each file.
+pub(crate) const FRAGMENT_OPENED: &str = "splitmuxsink-fragment-opened";
+pub(crate) const FRAGMENT_CLOSED: &str = "splitmuxsink-fragment-closed";
+
+/// Container of recorded files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Container {
+    /// Fragmented MP4, so a file cut short by a crash or a reconnect stays
+    /// playable.
+    #[default]
+    Mp4,
+    Matroska,
+}
+
+impl Container {
+    fn muxer(&self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4mux",
+            Container::Matroska => "matroskamux",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4",
+            Container::Matroska => "mkv",
+        }
+    }
+}
+
+/// Where and how `RtspPlayer::start_recording` writes files.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingConfig {
+    pub directory: PathBuf,
+    /// File names are `<prefix>-<n>.<ext>`, `n` counting up per file from
+    /// the highest one already in `directory`.
+    pub prefix: String,
+    pub container: Container,
+    /// Start a new file once the current one is this long.
+    pub max_duration: Option<Duration>,
+    /// Start a new file once the current one has this many bytes.
+    pub max_size: Option<u64>,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("."),
+            prefix: "recording".to_string(),
+            container: Container::default(),
+            max_duration: Some(Duration::from_secs(10 * 60)),
+            max_size: None,
+        }
+    }
+}
+
+impl RecordingConfig {
+    /// The file after the last one in `directory`, so reconnects, variant
+    /// switches and restarts never overwrite an earlier recording.
+    fn next_location(&self) -> PathBuf {
+        let extension = format!(".{}", self.container.extension());
+        let next = std::fs::read_dir(&self.directory)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                file_index(name.to_str()?, &self.prefix, &extension)
+            })
+            .max()
+            .map_or(0, |index| index + 1);
+        self.directory
+            .join(format!("{}-{:05}{}", self.prefix, next, extension))
+    }
+}
+
+/// `n` of a `<prefix>-<n><extension>` file name.
+fn file_index(name: &str, prefix: &str, extension: &str) -> Option<u64> {
+    let digits = name
+        .strip_prefix(prefix)?
+        .strip_prefix('-')?
+        .strip_suffix(extension)?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// `queue ! splitmuxsink` hanging off the tee in front of the decoder,
+/// recording the parsed stream as is.
+pub(crate) struct RecordingBranch {
+    tee_pad: gst::Pad,
+    queue: gst::Element,
+    pub(crate) splitmux: gst::Element,
+}
+
+impl RecordingBranch {
+    pub(crate) fn attach(
+        pipeline: &gst::Pipeline,
+        tee: &gst::Element,
+        config: &RecordingConfig,
+    ) -> Result<Self> {
+        let queue = make_gs_element("queue")?;
+        let splitmux = make_gs_element("splitmuxsink")?;
+        let location_config = config.clone();
+        splitmux.connect("format-location", false, move |_| {
+            let location = location_config.next_location();
+            Some(location.to_string_lossy().into_owned().to_value())
+        });
+        splitmux.set_property("muxer-factory", config.container.muxer());
+        if config.container == Container::Mp4 {
+            splitmux.set_property(
+                "muxer-properties",
+                gst::Structure::builder("properties")
+                    .field("fragment-duration", 1000u32)
+                    .build(),
+            );
+        }
+        if let Some(max_duration) = config.max_duration {
+            splitmux.set_property("max-size-time", max_duration.as_nanos() as u64);
+        }
+        if let Some(max_size) = config.max_size {
+            splitmux.set_property("max-size-bytes", max_size);
+        }
+
+        pipeline.add_many([&queue, &splitmux])?;
+        link_many(&[&queue, &splitmux])?;
+
+        // The muxer needs to start on a keyframe.
+        let queue_sink = queue.static_pad("sink").unwrap();
+        queue_sink.add_probe(gst::PadProbeType::BUFFER, |_, info| match info.buffer() {
+            Some(buffer) if buffer.flags().contains(gst::BufferFlags::DELTA_UNIT) => {
+                gst::PadProbeReturn::Drop
+            }
+            _ => gst::PadProbeReturn::Remove,
+        });
+
+        let tee_pad = tee
+            .request_pad_simple("src_%u")
+            .ok_or_else(|| PlayerError::link_failed(tee, &queue))?;
+        tee_pad
+            .link(&queue_sink)
+            .map_err(|_| PlayerError::link_failed(tee, &queue))?;
+        queue.sync_state_with_parent()?;
+        splitmux.sync_state_with_parent()?;
+        Ok(Self {
+            tee_pad,
+            queue,
+            splitmux,
+        })
+    }
+
+    /// Unlinks the branch from the tee and sends EOS down it, so the muxer
+    /// finishes the current file. `remove` it once `FRAGMENT_CLOSED` arrives.
+    /// Also works once the source has stopped streaming.
+    pub(crate) fn finish(&self) {
+        let queue = self.queue.clone();
+        self.tee_pad
+            .add_probe(gst::PadProbeType::IDLE, move |tee_pad, _| {
+                let queue_sink = queue.static_pad("sink").unwrap();
+                let _ = tee_pad.unlink(&queue_sink);
+                if let Some(tee) = tee_pad.parent_element() {
+                    tee.release_request_pad(tee_pad);
+                }
+                queue_sink.send_event(gst::event::Eos::new());
+                gst::PadProbeReturn::Remove
+            });
+    }
+
+    /// Drops the branch from `pipeline` right away, cutting its file short.
+    pub(crate) fn remove(&self, pipeline: &gst::Pipeline) {
+        if let Some(tee) = self.tee_pad.parent_element() {
+            tee.release_request_pad(&self.tee_pad);
+        }
+        for element in [&self.queue, &self.splitmux] {
+            let _ = element.set_state(gst::State::Null);
+            if let Err(err) = pipeline.remove(element) {
+                warn!("Failed to remove {}: {}", element.name(), err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_file_indices() {
+        assert_eq!(file_index("cam-00042.mp4", "cam", ".mp4"), Some(42));
+        assert_eq!(file_index("cam-7.mp4", "cam", ".mp4"), Some(7));
+        assert_eq!(file_index("cam-00042.mkv", "cam", ".mp4"), None);
+        assert_eq!(file_index("cam2-00042.mp4", "cam", ".mp4"), None);
+        assert_eq!(file_index("cam-.mp4", "cam", ".mp4"), None);
+        assert_eq!(file_index("cam--1.mp4", "cam", ".mp4"), None);
+    }
+
+    #[test]
+    fn continues_after_existing_files() {
+        let directory =
+            std::env::temp_dir().join(format!("rtsp-player-recording-test-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let config = RecordingConfig {
+            directory: directory.clone(),
+            prefix: "cam".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.next_location(), directory.join("cam-00000.mp4"));
+
+        for name in [
+            "cam-00000.mp4",
+            "cam-00003.mp4",
+            "cam-00009.mkv",
+            "other-00010.mp4",
+        ] {
+            std::fs::write(directory.join(name), b"").unwrap();
+        }
+        assert_eq!(config.next_location(), directory.join("cam-00004.mp4"));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
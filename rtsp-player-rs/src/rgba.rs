@@ -0,0 +1,194 @@
+use gst::prelude::*;
+use log::warn;
+
+use crate::config::TextureSizeMode;
+use crate::error::{PlayerError, Result};
+use crate::models::images::RawImage;
+use crate::state::VideoParams;
+use crate::timestamps::captured_at;
+use crate::utils::{link_many, make_gs_element};
+
+/// Name of the `appsink` inside the bin built by `make_rgba_sink`. Its sink
+/// pad carries the caps of the frames handed out.
+pub(crate) const APPSINK_NAME: &str = "rgba-appsink";
+
+/// Builds `videoconvert ! videoscale ! video/x-raw,format=RGBA ! appsink` as
+/// a bin and hands every sample to `on_frame` as a `RawImage`.
+///
+/// This is the path for machines without a GL context that can be shared
+/// with GStreamer; the frames are copied to system memory. `size_mode`
+/// decides what `videoscale` produces.
+pub fn make_rgba_sink(
+    size_mode: TextureSizeMode,
+    on_frame: impl Fn(RawImage) + Send + Sync + 'static,
+) -> Result<gst::Element> {
+    let bin = gst::Bin::builder().name("rgba-sink").build();
+    let convert = make_gs_element("videoconvert")?;
+    let scale = make_gs_element("videoscale")?;
+    let capsfilter = make_gs_element("capsfilter")?;
+    if let TextureSizeMode::Fixed { width, height } = size_mode {
+        scale.set_property("add-borders", true);
+        capsfilter.set_property("caps", rgba_caps(Some((width, height))));
+    } else {
+        capsfilter.set_property("caps", rgba_caps(None));
+    }
+    let appsink = gst_app::AppSink::builder()
+        .name(APPSINK_NAME)
+        .max_buffers(1)
+        .drop(true)
+        .build();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample(move |appsink| {
+                let sample = appsink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                match raw_image_from_sample(&sample) {
+                    Some(image) => on_frame(image),
+                    None => warn!("Dropping RGBA sample without usable caps or buffer"),
+                }
+                Ok(gst::FlowSuccess::Ok)
+            })
+            .build(),
+    );
+
+    let appsink = appsink.upcast::<gst::Element>();
+    bin.add_many([&convert, &scale, &capsfilter, &appsink])?;
+    link_many(&[&convert, &scale, &capsfilter, &appsink])?;
+    let ghost_pad = gst::GhostPad::with_target(&convert.static_pad("sink").unwrap())?;
+    bin.add_pad(&ghost_pad)
+        .map_err(|_| PlayerError::link_failed(&bin, &convert))?;
+
+    if let TextureSizeMode::Fit { .. } = size_mode {
+        // The target size depends on the stream's display size, so it is
+        // only known once caps arrive.
+        ghost_pad.connect_notify(Some("caps"), move |pad, _| {
+            let Some(params) = pad
+                .current_caps()
+                .and_then(|caps| VideoParams::from_caps(&caps))
+            else {
+                return;
+            };
+            if let Some(size) = params
+                .dw
+                .zip(params.dh)
+                .and_then(|(dw, dh)| size_mode.output_size(dw, dh))
+            {
+                capsfilter.set_property("caps", rgba_caps(Some(size)));
+            }
+        });
+    }
+    Ok(bin.upcast())
+}
+
+/// RGBA caps, scaled to square pixels of `size` when given.
+fn rgba_caps(size: Option<(u32, u32)>) -> gst::Caps {
+    let builder = gst::Caps::builder("video/x-raw").field("format", "RGBA");
+    match size {
+        Some((width, height)) => builder
+            .field("width", width as i32)
+            .field("height", height as i32)
+            .field("pixel-aspect-ratio", gst::Fraction::new(1, 1))
+            .build(),
+        None => builder.build(),
+    }
+}
+
+/// Copies an RGBA sample into a `RawImage`, keeping the row stride of the
+/// buffer. The buffer's `VideoMeta` wins over the caps for its layout, as
+/// hardware decoders and GL downloads pad rows their own way.
+pub(crate) fn raw_image_from_sample(sample: &gst::Sample) -> Option<RawImage> {
+    let info = gst_video::VideoInfo::from_caps(sample.caps()?).ok()?;
+    let buffer = sample.buffer()?;
+    let frame = gst_video::VideoFrameRef::from_buffer_ref_readable(buffer, &info).ok()?;
+    let stride = frame.plane_stride()[0] as usize;
+    let len = stride * frame.height() as usize;
+    let data = frame.plane_data(0).ok()?.get(..len)?.to_vec();
+    let mut image = RawImage::new_from_rgba_data_with_stride(
+        data,
+        frame.width(),
+        frame.height(),
+        stride as u32,
+    );
+    image.captured_at = captured_at(buffer);
+    Some(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn rgba_sink_delivers_strided_frames() {
+        gst::init().unwrap();
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let frames_clone = frames.clone();
+        let sink = make_rgba_sink(TextureSizeMode::FollowStream, move |image| {
+            frames_clone.lock().unwrap().push(image)
+        })
+        .unwrap();
+
+        let pipeline = gst::Pipeline::new();
+        let src = gst::ElementFactory::make("videotestsrc")
+            .property("num-buffers", 3)
+            .build()
+            .unwrap();
+        let caps = gst::ElementFactory::make("capsfilter")
+            .property(
+                "caps",
+                gst::Caps::builder("video/x-raw")
+                    .field("width", 33)
+                    .field("height", 17)
+                    .build(),
+            )
+            .build()
+            .unwrap();
+        pipeline.add_many([&src, &caps, &sink]).unwrap();
+        gst::Element::link_many([&src, &caps, &sink]).unwrap();
+        pipeline.set_state(gst::State::Playing).unwrap();
+        let bus = pipeline.bus().unwrap();
+        bus.timed_pop_filtered(
+            gst::ClockTime::from_seconds(5),
+            &[gst::MessageType::Eos, gst::MessageType::Error],
+        );
+        pipeline.set_state(gst::State::Null).unwrap();
+
+        let frames = frames.lock().unwrap();
+        assert!(!frames.is_empty());
+        let frame = &frames[0];
+        assert_eq!((frame.width, frame.height), (33, 17));
+        assert!(frame.stride >= 33 * 4);
+        assert_eq!(frame.data.len(), (frame.stride * frame.height) as usize);
+    }
+
+    #[test]
+    fn video_meta_layout_wins_over_caps() {
+        gst::init().unwrap();
+        // 2x2 RGBA with rows padded to 16 bytes, starting 4 bytes in.
+        let mut data = vec![0u8; 4 + 16 * 2];
+        data[4..12].copy_from_slice(&[1; 8]);
+        data[20..28].copy_from_slice(&[2; 8]);
+        let mut buffer = gst::Buffer::from_mut_slice(data);
+        gst_video::VideoMeta::add_full(
+            buffer.get_mut().unwrap(),
+            gst_video::VideoFrameFlags::empty(),
+            gst_video::VideoFormat::Rgba,
+            2,
+            2,
+            &[4],
+            &[16],
+        )
+        .unwrap();
+        let caps = gst_video::VideoInfo::builder(gst_video::VideoFormat::Rgba, 2, 2)
+            .build()
+            .unwrap()
+            .to_caps()
+            .unwrap();
+        let sample = gst::Sample::builder().buffer(&buffer).caps(&caps).build();
+
+        let image = raw_image_from_sample(&sample).unwrap();
+        assert_eq!(image.stride, 16);
+        assert_eq!(&image.data[..8], &[1; 8]);
+        assert_eq!(&image.data[16..24], &[2; 8]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+use little_exif::exif_tag::ExifTag;
+use little_exif::filetype::FileExtension;
+use little_exif::metadata::Metadata;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PlayerError, Result};
+use crate::models::images::{MomentoBoothExifTag, RawImage};
+use crate::rgba::raw_image_from_sample;
+use crate::timestamps::captured_at;
+
+/// How long converting the last frame to RGBA may take.
+const CONVERT_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(2);
+
+/// Encoding of a snapshot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotFormat {
+    #[default]
+    Jpeg,
+    Png,
+}
+
+/// Converts the sample held by a sink's `last-sample` into an RGBA
+/// `RawImage`, keeping the frame's capture time. GL memory is downloaded
+/// when the buffer gets mapped.
+pub(crate) fn raw_image_from_last_sample(sample: &gst::Sample) -> Result<RawImage> {
+    let mut caps = sample
+        .caps_owned()
+        .ok_or_else(|| snapshot_error("The last frame has no caps"))?;
+    // `videoconvert` only takes system memory caps; GL memory maps fine.
+    caps.make_mut().set_features_simple(None);
+    let buffer = sample
+        .buffer_owned()
+        .ok_or_else(|| snapshot_error("The last frame has no buffer"))?;
+    let sample = gst::Sample::builder().buffer(&buffer).caps(&caps).build();
+
+    let rgba_caps = gst::Caps::builder("video/x-raw")
+        .field("format", "RGBA")
+        .build();
+    let rgba = gst_video::convert_sample(&sample, &rgba_caps, CONVERT_TIMEOUT)
+        .map_err(|err| snapshot_error(&err.to_string()))?;
+    let mut image = raw_image_from_sample(&rgba)
+        .ok_or_else(|| snapshot_error("Could not map the converted frame"))?;
+    image.captured_at = captured_at(&buffer);
+    Ok(image)
+}
+
+/// Encodes `image` as `format` and embeds `tags` as EXIF. `quality` only
+/// applies to JPEG and is clamped to 1..=100.
+pub fn encode(
+    image: &RawImage,
+    format: SnapshotFormat,
+    quality: u8,
+    tags: Vec<MomentoBoothExifTag>,
+) -> Result<Vec<u8>> {
+    let pixels = packed_rgba(image);
+    let mut encoded = Vec::new();
+    let result = match format {
+        SnapshotFormat::Jpeg => {
+            // JPEG has no alpha channel.
+            let rgb: Vec<u8> = pixels
+                .chunks_exact(4)
+                .flat_map(|pixel| &pixel[..3])
+                .copied()
+                .collect();
+            JpegEncoder::new_with_quality(Cursor::new(&mut encoded), quality.clamp(1, 100))
+                .write_image(&rgb, image.width, image.height, ExtendedColorType::Rgb8)
+        }
+        SnapshotFormat::Png => PngEncoder::new(Cursor::new(&mut encoded)).write_image(
+            &pixels,
+            image.width,
+            image.height,
+            ExtendedColorType::Rgba8,
+        ),
+    };
+    result.map_err(|err| snapshot_error(&err.to_string()))?;
+
+    let mut metadata = Metadata::new();
+    for tag in tags {
+        metadata.set_tag(tag.into());
+    }
+    let file_type = match format {
+        SnapshotFormat::Jpeg => FileExtension::JPEG,
+        SnapshotFormat::Png => FileExtension::PNG {
+            as_zTXt_chunk: true,
+        },
+    };
+    metadata
+        .write_to_vec(&mut encoded, file_type)
+        .map_err(|err| snapshot_error(&err.to_string()))?;
+    Ok(encoded)
+}
+
+/// Rows of `image` without the padding of its stride.
+fn packed_rgba(image: &RawImage) -> Vec<u8> {
+    let row_len = image.width as usize * 4;
+    if image.stride as usize == row_len {
+        return image.data.clone();
+    }
+    image
+        .data
+        .chunks(image.stride as usize)
+        .take(image.height as usize)
+        .flat_map(|row| &row[..row_len])
+        .copied()
+        .collect()
+}
+
+fn snapshot_error(message: &str) -> PlayerError {
+    PlayerError::Snapshot {
+        message: message.to_string(),
+    }
+}
+
+impl From<MomentoBoothExifTag> for ExifTag {
+    fn from(tag: MomentoBoothExifTag) -> Self {
+        match tag {
+            MomentoBoothExifTag::ImageDescription(description) => {
+                ExifTag::ImageDescription(description)
+            }
+            MomentoBoothExifTag::Software(software) => ExifTag::Software(software),
+            MomentoBoothExifTag::CreateDate(date) => {
+                ExifTag::CreateDate(date.format("%Y:%m:%d %H:%M:%S").to_string())
+            }
+            MomentoBoothExifTag::Orientation(orientation) => {
+                ExifTag::Orientation(vec![orientation as u16])
+            }
+            MomentoBoothExifTag::MakerNote(note) => ExifTag::MakerNote(note.into_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::images::ExifOrientation;
+
+    #[test]
+    fn encodes_strided_frames_with_exif() {
+        // 2x2 red frame with 4 bytes of padding per row.
+        let row = [255, 0, 0, 255, 255, 0, 0, 255, 0, 0, 0, 0];
+        let image = RawImage::new_from_rgba_data_with_stride(row.repeat(2), 2, 2, 12);
+        assert_eq!(packed_rgba(&image).len(), 16);
+
+        let tags = || {
+            vec![
+                MomentoBoothExifTag::ImageDescription("rtsp://camera/main".to_string()),
+                MomentoBoothExifTag::Orientation(ExifOrientation::TopLeft),
+            ]
+        };
+        let jpeg = encode(&image, SnapshotFormat::Jpeg, 90, tags()).unwrap();
+        assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+        assert!(jpeg.windows(4).any(|w| w == b"Exif"));
+
+        let png = encode(&image, SnapshotFormat::Png, 90, tags()).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+}
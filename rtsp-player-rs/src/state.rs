@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use crate::audio::AudioDevice;
+use crate::tracks::{SelectedTracks, Tracks};
+
+/// Snapshot of what the player is doing, published through
+/// `RtspPlayer::watch_state`. Mirrors the Dart `PlayerState` DTO.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerState {
+    pub playing: bool,
+    /// The server ended the stream.
+    pub completed: bool,
+    pub buffering: bool,
+    pub buffering_percentage: f64,
+    /// Running time of the pipeline.
+    pub position: Duration,
+    /// Latency reported by the pipeline latency query.
+    pub latency: Duration,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Size of the texture, which differs from `width`x`height` when the
+    /// frames are scaled.
+    pub texture_width: Option<u32>,
+    pub texture_height: Option<u32>,
+    pub video_params: VideoParams,
+    pub audio_params: AudioParams,
+    /// Frames rendered and dropped, as reported by the QoS messages of the
+    /// video sink.
+    pub frames_rendered: u64,
+    pub frames_dropped: u64,
+    /// Volume in percent, 100 being unchanged.
+    pub volume: f64,
+    pub muted: bool,
+    pub audio_device: AudioDevice,
+    /// Streams advertised by the server.
+    pub tracks: Tracks,
+    pub track: SelectedTracks,
+}
+
+impl Default for PlayerState {
+    fn default() -> Self {
+        Self {
+            playing: false,
+            completed: false,
+            buffering: false,
+            buffering_percentage: 0.0,
+            position: Duration::ZERO,
+            latency: Duration::ZERO,
+            width: None,
+            height: None,
+            texture_width: None,
+            texture_height: None,
+            video_params: VideoParams::default(),
+            audio_params: AudioParams::default(),
+            frames_rendered: 0,
+            frames_dropped: 0,
+            volume: 100.0,
+            muted: false,
+            audio_device: AudioDevice::auto(),
+            tracks: Tracks::default(),
+            track: SelectedTracks::default(),
+        }
+    }
+}
+
+/// Parameters of the negotiated raw video, taken from the sink caps.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VideoParams {
+    pub pixelformat: Option<String>,
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+    /// Size scaled for the pixel aspect ratio.
+    pub dw: Option<u32>,
+    pub dh: Option<u32>,
+    pub aspect: Option<f64>,
+    pub par: Option<f64>,
+    pub colorimetry: Option<String>,
+    pub fps: Option<f64>,
+}
+
+impl VideoParams {
+    pub(crate) fn from_caps(caps: &gst::CapsRef) -> Option<Self> {
+        let structure = caps.structure(0)?;
+        let w = structure.get::<i32>("width").ok().map(|w| w as u32);
+        let h = structure.get::<i32>("height").ok().map(|h| h as u32);
+        let par = structure
+            .get::<gst::Fraction>("pixel-aspect-ratio")
+            .ok()
+            .map(|par| par.numer() as f64 / par.denom() as f64);
+        let (dw, dh) = match (w, h, par) {
+            (Some(w), Some(h), Some(par)) if par > 1.0 => (Some((w as f64 * par) as u32), Some(h)),
+            (Some(w), Some(h), Some(par)) if par < 1.0 => (Some(w), Some((h as f64 / par) as u32)),
+            _ => (w, h),
+        };
+
+        Some(Self {
+            pixelformat: structure.get::<String>("format").ok(),
+            w,
+            h,
+            dw,
+            dh,
+            aspect: dw.zip(dh).map(|(dw, dh)| dw as f64 / dh as f64),
+            par,
+            colorimetry: structure.get::<String>("colorimetry").ok(),
+            fps: structure
+                .get::<gst::Fraction>("framerate")
+                .ok()
+                .filter(|fps| fps.denom() != 0)
+                .map(|fps| fps.numer() as f64 / fps.denom() as f64),
+        })
+    }
+}
+
+/// Parameters of the negotiated raw audio.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AudioParams {
+    pub format: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub channel_count: Option<u32>,
+}
+
+impl AudioParams {
+    pub(crate) fn from_caps(caps: &gst::CapsRef) -> Option<Self> {
+        let structure = caps.structure(0)?;
+        Some(Self {
+            format: structure.get::<String>("format").ok(),
+            sample_rate: structure.get::<i32>("rate").ok().map(|rate| rate as u32),
+            channel_count: structure
+                .get::<i32>("channels")
+                .ok()
+                .map(|channels| channels as u32),
+        })
+    }
+}
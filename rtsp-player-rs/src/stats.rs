@@ -0,0 +1,195 @@
+use std::time::{Duration, Instant};
+
+use gst::glib;
+use gst::prelude::*;
+
+/// Link and decoding statistics of a player, see `RtspPlayer::watch_stats`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StreamStats {
+    /// Bits per second received over RTP, all streams together.
+    pub bitrate: u64,
+    /// Video frames per second arriving from the network.
+    pub fps_received: f64,
+    /// Video frames per second reaching the video sink.
+    pub fps_rendered: f64,
+    pub packets_received: u64,
+    /// Packets the jitterbuffers gave up waiting for.
+    pub packets_lost: u64,
+    /// Average packet jitter measured by the jitterbuffers, of the worst
+    /// stream.
+    pub jitter: Duration,
+    /// Jitterbuffer and processing latency of the pipeline.
+    pub latency: Duration,
+    /// Frames dropped by the video sink, as reported by QoS messages.
+    pub frames_dropped: u64,
+}
+
+/// Counters summed over the RTP sessions and jitterbuffers of `rtspsrc`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct RtpTotals {
+    pub(crate) octets_received: u64,
+    pub(crate) packets_received: u64,
+    pub(crate) packets_lost: u64,
+    pub(crate) jitter: Duration,
+}
+
+impl RtpTotals {
+    /// Reads the `stats` of the `rtpsession`s and `rtpjitterbuffer`s
+    /// inside `rtspsrc`.
+    pub(crate) fn from_source(source: &gst::Element) -> Self {
+        let mut totals = RtpTotals::default();
+        let Some(bin) = source.downcast_ref::<gst::Bin>() else {
+            return totals;
+        };
+        for element in bin.iterate_recurse().into_iter().flatten() {
+            let Some(factory) = element.factory() else {
+                continue;
+            };
+            match factory.name().as_str() {
+                "rtpsession" => {
+                    totals.add_session_stats(&element.property::<gst::Structure>("stats"))
+                }
+                "rtpjitterbuffer" => {
+                    totals.add_jitterbuffer_stats(&element.property::<gst::Structure>("stats"))
+                }
+                _ => (),
+            }
+        }
+        totals
+    }
+
+    /// Adds the remote senders of an `rtpsession`'s `stats`.
+    fn add_session_stats(&mut self, stats: &gst::StructureRef) {
+        let Ok(sources) = stats.get::<glib::ValueArray>("source-stats") else {
+            return;
+        };
+        for source in sources.iter() {
+            let Ok(source) = source.get::<gst::Structure>() else {
+                continue;
+            };
+            if source.get::<bool>("internal").unwrap_or(true) {
+                continue;
+            }
+            self.octets_received += source.get::<u64>("octets-received").unwrap_or(0);
+            self.packets_received += source.get::<u64>("packets-received").unwrap_or(0);
+        }
+    }
+
+    fn add_jitterbuffer_stats(&mut self, stats: &gst::StructureRef) {
+        self.packets_lost += stats.get::<u64>("num-lost").unwrap_or(0);
+        // The worst stream is the one worth looking at.
+        let jitter = Duration::from_nanos(stats.get::<u64>("avg-jitter").unwrap_or(0));
+        self.jitter = self.jitter.max(jitter);
+    }
+}
+
+/// Turns counters into rates between two samples.
+#[derive(Debug)]
+pub(crate) struct StatsSampler {
+    at: Instant,
+    octets_received: u64,
+    frames_received: u64,
+    frames_rendered: u64,
+}
+
+impl Default for StatsSampler {
+    fn default() -> Self {
+        Self {
+            at: Instant::now(),
+            octets_received: 0,
+            frames_received: 0,
+            frames_rendered: 0,
+        }
+    }
+}
+
+impl StatsSampler {
+    /// Counters start over with a new source, so anything smaller than the
+    /// last sample counts from zero.
+    pub(crate) fn sample(
+        &mut self,
+        now: Instant,
+        totals: RtpTotals,
+        frames_received: u64,
+        frames_rendered: u64,
+    ) -> StreamStats {
+        let elapsed = now.duration_since(self.at).as_secs_f64();
+        let rate = |current: u64, previous: u64| {
+            if elapsed <= 0.0 {
+                return 0.0;
+            }
+            let delta = if current >= previous {
+                current - previous
+            } else {
+                current
+            };
+            delta as f64 / elapsed
+        };
+        let stats = StreamStats {
+            bitrate: (rate(totals.octets_received, self.octets_received) * 8.0) as u64,
+            fps_received: rate(frames_received, self.frames_received),
+            fps_rendered: rate(frames_rendered, self.frames_rendered),
+            packets_received: totals.packets_received,
+            packets_lost: totals.packets_lost,
+            jitter: totals.jitter,
+            ..Default::default()
+        };
+        *self = Self {
+            at: now,
+            octets_received: totals.octets_received,
+            frames_received,
+            frames_rendered,
+        };
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_and_rates_from_rtp_stats() {
+        gst::init().unwrap();
+        let source = |internal: bool, octets: u64| {
+            gst::Structure::builder("application/x-rtp-source-stats")
+                .field("internal", internal)
+                .field("octets-received", octets)
+                .field("packets-received", octets / 1000)
+                .build()
+        };
+        let session = gst::Structure::builder("application/x-rtp-session-stats")
+            .field(
+                "source-stats",
+                glib::ValueArray::new([source(true, 0), source(false, 250_000)]),
+            )
+            .build();
+        let jitterbuffer = gst::Structure::builder("application/x-rtp-jitterbuffer-stats")
+            .field("num-lost", 3u64)
+            .field("avg-jitter", 4_000_000u64)
+            .build();
+
+        let mut totals = RtpTotals::default();
+        totals.add_session_stats(&session);
+        totals.add_jitterbuffer_stats(&jitterbuffer);
+        assert_eq!(totals.packets_received, 250);
+        assert_eq!(totals.packets_lost, 3);
+        assert_eq!(totals.jitter, Duration::from_millis(4));
+
+        let start = Instant::now();
+        let mut sampler = StatsSampler {
+            at: start,
+            octets_received: 0,
+            frames_received: 0,
+            frames_rendered: 0,
+        };
+        let stats = sampler.sample(start + Duration::from_secs(2), totals, 50, 48);
+        assert_eq!(stats.bitrate, 1_000_000);
+        assert_eq!(stats.fps_received, 25.0);
+        assert_eq!(stats.fps_rendered, 24.0);
+
+        // A new source starts counting from zero again.
+        let stats = sampler.sample(start + Duration::from_secs(3), RtpTotals::default(), 10, 10);
+        assert_eq!(stats.fps_received, 10.0);
+    }
+}
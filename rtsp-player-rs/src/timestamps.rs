@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use gst::prelude::*;
+
+/// Reference of the `ReferenceTimestampMeta`s holding a frame's NTP time,
+/// as added by `rtspsrc` from RTCP sender reports.
+const NTP_CAPS: &str = "timestamp/x-ntp";
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// RTP header extension profile of the ONVIF replay extension.
+const ONVIF_PROFILE: u16 = 0xABAC;
+
+/// The camera's wall-clock time of `buffer`, if `rtspsrc` or the ONVIF
+/// probe tagged it with an NTP timestamp.
+pub(crate) fn captured_at(buffer: &gst::BufferRef) -> Option<DateTime<Utc>> {
+    buffer
+        .iter_meta::<gst::ReferenceTimestampMeta>()
+        .find(|meta| {
+            meta.reference()
+                .structure(0)
+                .is_some_and(|s| s.name() == NTP_CAPS)
+        })
+        .and_then(|meta| ntp_to_utc(meta.timestamp().nseconds()))
+}
+
+/// Converts nanoseconds since the NTP epoch to UTC.
+fn ntp_to_utc(ntp_ns: u64) -> Option<DateTime<Utc>> {
+    let unix_ns = ntp_ns.checked_sub(NTP_UNIX_OFFSET * 1_000_000_000)?;
+    Some(DateTime::from_timestamp_nanos(i64::try_from(unix_ns).ok()?))
+}
+
+/// The NTP time of the ONVIF replay header extension of an RTP packet, in
+/// nanoseconds since the NTP epoch.
+fn onvif_ntp(rtp: &[u8]) -> Option<u64> {
+    let first = *rtp.first()?;
+    if first >> 6 != 2 || first & 0x10 == 0 {
+        return None;
+    }
+    let offset = 12 + 4 * (first & 0x0f) as usize;
+    let extension = rtp.get(offset..)?;
+    if u16::from_be_bytes([*extension.first()?, *extension.get(1)?]) != ONVIF_PROFILE {
+        return None;
+    }
+    let ntp = u64::from_be_bytes(extension.get(4..12)?.try_into().ok()?);
+    let seconds = ntp >> 32;
+    let fraction = ntp & 0xffff_ffff;
+    Some(seconds * 1_000_000_000 + ((fraction * 1_000_000_000) >> 32))
+}
+
+/// Tags RTP packets leaving `pad` that carry the ONVIF replay extension
+/// with their NTP time, unless the RTCP mapping already did.
+pub(crate) fn add_onvif_probe(pad: &gst::Pad) {
+    pad.add_probe(gst::PadProbeType::BUFFER, |_, info| {
+        let Some(buffer) = info.buffer_mut() else {
+            return gst::PadProbeReturn::Ok;
+        };
+        let ntp = buffer.map_readable().ok().and_then(|map| onvif_ntp(&map));
+        if let Some(ntp) = ntp {
+            if captured_at(buffer).is_none() {
+                let caps = gst::Caps::new_empty_simple(NTP_CAPS);
+                gst::ReferenceTimestampMeta::add(
+                    buffer.make_mut(),
+                    &caps,
+                    gst::ClockTime::from_nseconds(ntp),
+                    gst::ClockTime::NONE,
+                );
+            }
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rtp_with_extension(profile: u16, ntp: u64) -> Vec<u8> {
+        // One CSRC, then the extension header and its 3 words.
+        let mut packet = vec![0x91, 96, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 2];
+        packet.extend_from_slice(&profile.to_be_bytes());
+        packet.extend_from_slice(&3u16.to_be_bytes());
+        packet.extend_from_slice(&ntp.to_be_bytes());
+        packet.extend_from_slice(&[0x80, 0, 0, 0]);
+        packet
+    }
+
+    #[test]
+    fn reads_the_onvif_replay_extension() {
+        let ntp = (3_900_000_000u64 << 32) | 0x8000_0000;
+        assert_eq!(
+            onvif_ntp(&rtp_with_extension(ONVIF_PROFILE, ntp)),
+            Some(3_900_000_000 * 1_000_000_000 + 500_000_000)
+        );
+        assert_eq!(onvif_ntp(&rtp_with_extension(0xBEDE, ntp)), None);
+        // No extension bit.
+        assert_eq!(onvif_ntp(&[0x80, 96, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1]), None);
+        assert_eq!(onvif_ntp(&[0x90, 96]), None);
+    }
+
+    #[test]
+    fn converts_ntp_to_utc() {
+        let at = ntp_to_utc((NTP_UNIX_OFFSET + 1_700_000_000) * 1_000_000_000 + 250_000_000);
+        assert_eq!(at.unwrap().to_rfc3339(), "2023-11-14T22:13:20.250+00:00");
+        assert_eq!(ntp_to_utc(1), None);
+    }
+
+    #[test]
+    fn captured_at_reads_the_ntp_meta() {
+        gst::init().unwrap();
+        let mut buffer = gst::Buffer::new();
+        assert_eq!(captured_at(&buffer), None);
+        gst::ReferenceTimestampMeta::add(
+            buffer.get_mut().unwrap(),
+            &gst::Caps::new_empty_simple("timestamp/x-unix"),
+            gst::ClockTime::from_seconds(1),
+            gst::ClockTime::NONE,
+        );
+        assert_eq!(captured_at(&buffer), None);
+        gst::ReferenceTimestampMeta::add(
+            buffer.get_mut().unwrap(),
+            &gst::Caps::new_empty_simple(NTP_CAPS),
+            gst::ClockTime::from_seconds(NTP_UNIX_OFFSET + 60),
+            gst::ClockTime::NONE,
+        );
+        assert_eq!(captured_at(&buffer), DateTime::from_timestamp(60, 0));
+    }
+}
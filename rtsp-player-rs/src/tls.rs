@@ -0,0 +1,370 @@
+use std::path::PathBuf;
+
+use gio::prelude::*;
+use gst::glib::{self, subclass::prelude::*};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{post_error, PlayerError, Result};
+
+/// TLS options for `rtsps://` sources.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM bundle of trusted CAs. The system store is used when unset.
+    pub ca_file: Option<PathBuf>,
+    /// PEM client certificate, optionally including its private key.
+    pub client_certificate: Option<PathBuf>,
+    /// PEM private key, when it is not part of `client_certificate`.
+    pub client_key: Option<PathBuf>,
+    pub validation: TlsValidation,
+}
+
+/// How the server certificate is checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TlsValidation {
+    /// Full validation against the CA store.
+    #[default]
+    Strict,
+    /// Full validation, except that an unknown issuer is accepted.
+    AcceptSelfSigned,
+    /// Only accept a certificate whose SHA-256 fingerprint of the DER
+    /// encoding matches, given in hex with or without `:` separators.
+    PinnedFingerprint(String),
+}
+
+impl TlsConfig {
+    /// Wires the TLS options into `rtspsrc`'s `tls-database`,
+    /// `tls-validation-flags`, `tls-interaction` and `accept-certificate`.
+    pub(crate) fn apply_to_rtspsrc(&self, rtspsrc: &gst::Element) -> Result<()> {
+        let database = match &self.ca_file {
+            Some(ca_file) => Some(
+                gio::TlsFileDatabase::new(ca_file)
+                    .map_err(tls_error)?
+                    .upcast::<gio::TlsDatabase>(),
+            ),
+            None => None,
+        };
+
+        if let Some(cert_file) = &self.client_certificate {
+            let certificate = match &self.client_key {
+                Some(key_file) => gio::TlsCertificate::from_files(cert_file, key_file),
+                None => gio::TlsCertificate::from_file(cert_file),
+            }
+            .map_err(tls_error)?;
+            rtspsrc.set_property("tls-interaction", ClientCertInteraction::new(certificate));
+        }
+
+        // GLib 2.72 and later validate everything no matter the flags.
+        rtspsrc.set_property(
+            "tls-validation-flags",
+            gio::TlsCertificateFlags::VALIDATE_ALL,
+        );
+        if self.validation == TlsValidation::Strict {
+            if let Some(database) = database {
+                rtspsrc.set_property("tls-database", database);
+            }
+            return Ok(());
+        }
+
+        // `accept-certificate` only fires when validation fails, so
+        // `rtspsrc` gets a database that trusts nothing and the real
+        // checks happen in the handler, for every certificate.
+        let database = database.unwrap_or_else(|| gio::TlsBackend::default().default_database());
+        rtspsrc.set_property("tls-database", TrustNothingDatabase::new());
+        let validation = self.validation.clone();
+        rtspsrc.connect("accept-certificate", false, move |args| {
+            let rtspsrc = args[0].get::<gst::Element>().ok()?;
+            let connection = args[1].get::<gio::TlsConnection>().ok()?;
+            let certificate = args[2].get::<gio::TlsCertificate>().ok()?;
+            let identity = connection
+                .dynamic_cast_ref::<gio::TlsClientConnection>()
+                .and_then(|connection| connection.server_identity());
+            let errors = database
+                .verify_chain(
+                    &certificate,
+                    gio::TLS_DATABASE_PURPOSE_AUTHENTICATE_SERVER,
+                    identity.as_ref(),
+                    connection.interaction().as_ref(),
+                    gio::TlsDatabaseVerifyFlags::NONE,
+                    gio::Cancellable::NONE,
+                )
+                .unwrap_or(gio::TlsCertificateFlags::GENERIC_ERROR);
+            let accepted = validation.accepts(&certificate, errors);
+            if !accepted {
+                // `rtspsrc` only reports a failed connection, this keeps the
+                // reason.
+                post_error(
+                    &rtspsrc,
+                    PlayerError::Tls {
+                        message: format!("Rejected the server certificate ({:?})", errors),
+                    },
+                );
+            }
+            Some(accepted.to_value())
+        });
+        Ok(())
+    }
+}
+
+impl TlsValidation {
+    /// Whether a certificate the CA store found `errors` with is
+    /// acceptable.
+    fn accepts(&self, certificate: &gio::TlsCertificate, errors: gio::TlsCertificateFlags) -> bool {
+        let tolerated = match self {
+            TlsValidation::Strict => gio::TlsCertificateFlags::empty(),
+            TlsValidation::AcceptSelfSigned => gio::TlsCertificateFlags::UNKNOWN_CA,
+            TlsValidation::PinnedFingerprint(pinned) => {
+                let fingerprint = certificate_fingerprint(certificate);
+                if fingerprint != normalize_fingerprint(pinned) {
+                    warn!("Rejecting TLS certificate with fingerprint {}", fingerprint);
+                    return false;
+                }
+                // The pin stands in for the issuer and the host name, the
+                // certificate still has to be valid.
+                gio::TlsCertificateFlags::UNKNOWN_CA | gio::TlsCertificateFlags::BAD_IDENTITY
+            }
+        };
+        let remaining = errors.difference(tolerated);
+        if !remaining.is_empty() {
+            warn!("Rejecting TLS certificate: {:?}", remaining);
+        }
+        remaining.is_empty()
+    }
+}
+
+fn tls_error(err: glib::Error) -> PlayerError {
+    PlayerError::Tls {
+        message: err.to_string(),
+    }
+}
+
+fn normalize_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn certificate_fingerprint(certificate: &gio::TlsCertificate) -> String {
+    let der = certificate.property::<glib::ByteArray>("certificate");
+    Sha256::digest(&der)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use super::*;
+
+    #[derive(Default)]
+    pub struct ClientCertInteraction {
+        pub(super) certificate: OnceLock<gio::TlsCertificate>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ClientCertInteraction {
+        const NAME: &'static str = "RtspPlayerClientCertInteraction";
+        type Type = super::ClientCertInteraction;
+        type ParentType = gio::TlsInteraction;
+    }
+
+    impl ObjectImpl for ClientCertInteraction {}
+
+    #[derive(Default)]
+    pub struct TrustNothingDatabase;
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TrustNothingDatabase {
+        const NAME: &'static str = "RtspPlayerTrustNothingDatabase";
+        type Type = super::TrustNothingDatabase;
+        type ParentType = gio::TlsDatabase;
+    }
+
+    impl ObjectImpl for TrustNothingDatabase {}
+}
+
+glib::wrapper! {
+    /// Answers the server's certificate request with a fixed client
+    /// certificate.
+    struct ClientCertInteraction(ObjectSubclass<imp::ClientCertInteraction>)
+        @extends gio::TlsInteraction;
+}
+
+impl ClientCertInteraction {
+    fn new(certificate: gio::TlsCertificate) -> Self {
+        let interaction: Self = glib::Object::new();
+        let _ = interaction.imp().certificate.set(certificate);
+        interaction
+    }
+}
+
+glib::wrapper! {
+    /// Fails every chain with `UNKNOWN_CA`, so `accept-certificate` gets
+    /// the final say.
+    struct TrustNothingDatabase(ObjectSubclass<imp::TrustNothingDatabase>)
+        @extends gio::TlsDatabase;
+}
+
+impl TrustNothingDatabase {
+    fn new() -> Self {
+        glib::Object::new()
+    }
+}
+
+/// The vfunc overrides of `TrustNothingDatabase` and
+/// `ClientCertInteraction`. gio has no subclassing support for
+/// `GTlsDatabase` or `GTlsInteraction`, so this is the one place that
+/// installs vfuncs by hand, the way a C subclass would in its `class_init`.
+///
+/// Safety invariants:
+/// - `class_init` only runs for the class of our own subclass, and
+///   `default_class_init` has set up the parent class first. Overriding a
+///   vfunc is writing its field of the parent class struct.
+/// - The function types come from gio-sys, so the signatures are checked
+///   against the C declarations.
+/// - A vfunc is only called on instances of the class it was installed on,
+///   so casting `interaction` to our instance type is sound. Pointers are
+///   only borrowed for the duration of the call.
+/// - Returning without touching `error` reports success, and a `NULL`
+///   issuer is gio's "none found".
+mod vfuncs {
+    use glib::translate::*;
+
+    use super::*;
+
+    unsafe impl IsSubclassable<imp::TrustNothingDatabase> for gio::TlsDatabase {
+        fn class_init(class: &mut glib::Class<Self>) {
+            Self::default_class_init(class);
+            let klass = class.as_mut();
+            klass.verify_chain = Some(verify_chain_untrusted);
+            klass.lookup_certificate_issuer = Some(lookup_no_issuer);
+        }
+    }
+
+    unsafe extern "C" fn verify_chain_untrusted(
+        _database: *mut gio::ffi::GTlsDatabase,
+        _chain: *mut gio::ffi::GTlsCertificate,
+        _purpose: *const std::ffi::c_char,
+        _identity: *mut gio::ffi::GSocketConnectable,
+        _interaction: *mut gio::ffi::GTlsInteraction,
+        _flags: gio::ffi::GTlsDatabaseVerifyFlags,
+        _cancellable: *mut gio::ffi::GCancellable,
+        _error: *mut *mut glib::ffi::GError,
+    ) -> gio::ffi::GTlsCertificateFlags {
+        gio::ffi::G_TLS_CERTIFICATE_UNKNOWN_CA
+    }
+
+    unsafe extern "C" fn lookup_no_issuer(
+        _database: *mut gio::ffi::GTlsDatabase,
+        _certificate: *mut gio::ffi::GTlsCertificate,
+        _interaction: *mut gio::ffi::GTlsInteraction,
+        _flags: gio::ffi::GTlsDatabaseLookupFlags,
+        _cancellable: *mut gio::ffi::GCancellable,
+        _error: *mut *mut glib::ffi::GError,
+    ) -> *mut gio::ffi::GTlsCertificate {
+        std::ptr::null_mut()
+    }
+
+    unsafe impl IsSubclassable<imp::ClientCertInteraction> for gio::TlsInteraction {
+        fn class_init(class: &mut glib::Class<Self>) {
+            Self::default_class_init(class);
+            let klass = class.as_mut();
+            klass.request_certificate = Some(request_certificate);
+        }
+    }
+
+    unsafe extern "C" fn request_certificate(
+        interaction: *mut gio::ffi::GTlsInteraction,
+        connection: *mut gio::ffi::GTlsConnection,
+        _flags: gio::ffi::GTlsCertificateRequestFlags,
+        _cancellable: *mut gio::ffi::GCancellable,
+        _error: *mut *mut glib::ffi::GError,
+    ) -> gio::ffi::GTlsInteractionResult {
+        let interaction: Borrowed<ClientCertInteraction> = from_glib_borrow(
+            interaction as *mut <imp::ClientCertInteraction as ObjectSubclass>::Instance,
+        );
+        let connection: Borrowed<gio::TlsConnection> = from_glib_borrow(connection);
+        match interaction.imp().certificate.get() {
+            Some(certificate) => {
+                connection.set_certificate(certificate);
+                gio::ffi::G_TLS_INTERACTION_HANDLED
+            }
+            None => gio::ffi::G_TLS_INTERACTION_UNHANDLED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Self-signed, for `camera.local`, valid until 2126.
+    const CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIIBhTCCASugAwIBAgIUVmzWdL45uqLUEbX+2MYmzRgGZdwwCgYIKoZIzj0EAwIw
+FzEVMBMGA1UEAwwMY2FtZXJhLmxvY2FsMCAXDTI2MTAxODEwMDIxM1oYDzIxMjYw
+OTI0MTAwMjEzWjAXMRUwEwYDVQQDDAxjYW1lcmEubG9jYWwwWTATBgcqhkjOPQIB
+BggqhkjOPQMBBwNCAARFXiCJKRX1A4myONNs3JRrAWe5TU8YECQxPKVbSipy47Y1
+n58xf1r2e+rFFvGpyaPmBOpDGYGDg0VUXxPghdauo1MwUTAdBgNVHQ4EFgQUEq5L
+CxMx3MqgCeohtrcJobC8ImEwHwYDVR0jBBgwFoAUEq5LCxMx3MqgCeohtrcJobC8
+ImEwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBFAiBDK2sr/9YoJQIg
+gGsVxZc4vmv8+VIGab9Mg86btdQfxAIhAOs2pXbACcfG7SwzcC/u1Z1G3wVH1Nco
+9bpkguFRPXuF
+-----END CERTIFICATE-----
+";
+    const FINGERPRINT: &str =
+        "66:0A:3C:B1:37:1C:CB:A7:6A:AD:C2:87:7B:5B:E2:74:53:3A:0D:01:84:30:DC:26:97:31:BB:40:20:68:4E:44";
+
+    #[test]
+    fn fingerprints_ignore_separators_and_case() {
+        assert_eq!(normalize_fingerprint("AB:cd:01"), "abcd01");
+        assert_eq!(normalize_fingerprint("abcd01"), "abcd01");
+    }
+
+    #[test]
+    fn pinned_fingerprint_decides_regardless_of_the_ca() {
+        let certificate = gio::TlsCertificate::from_pem(CERTIFICATE).unwrap();
+        let unknown_ca = gio::TlsCertificateFlags::UNKNOWN_CA;
+        let pinned = TlsValidation::PinnedFingerprint(FINGERPRINT.to_string());
+        assert!(pinned.accepts(&certificate, unknown_ca));
+        assert!(!pinned.accepts(&certificate, gio::TlsCertificateFlags::EXPIRED));
+
+        // A certificate any CA would vouch for still has to match the pin.
+        let other = TlsValidation::PinnedFingerprint("00".repeat(32));
+        assert!(!other.accepts(&certificate, gio::TlsCertificateFlags::empty()));
+        assert!(!other.accepts(&certificate, unknown_ca));
+    }
+
+    #[test]
+    fn trust_nothing_database_fails_every_chain() {
+        let certificate = gio::TlsCertificate::from_pem(CERTIFICATE).unwrap();
+        let database = TrustNothingDatabase::new();
+        let errors = database
+            .verify_chain(
+                &certificate,
+                gio::TLS_DATABASE_PURPOSE_AUTHENTICATE_SERVER,
+                None::<&gio::SocketConnectable>,
+                None::<&gio::TlsInteraction>,
+                gio::TlsDatabaseVerifyFlags::NONE,
+                gio::Cancellable::NONE,
+            )
+            .unwrap();
+        assert_eq!(errors, gio::TlsCertificateFlags::UNKNOWN_CA);
+    }
+
+    #[test]
+    fn self_signed_only_tolerates_the_unknown_ca() {
+        let certificate = gio::TlsCertificate::from_pem(CERTIFICATE).unwrap();
+        let validation = TlsValidation::AcceptSelfSigned;
+        assert!(validation.accepts(&certificate, gio::TlsCertificateFlags::UNKNOWN_CA));
+        assert!(!validation.accepts(
+            &certificate,
+            gio::TlsCertificateFlags::UNKNOWN_CA | gio::TlsCertificateFlags::NOT_ACTIVATED
+        ));
+        assert!(!TlsValidation::Strict.accepts(&certificate, gio::TlsCertificateFlags::UNKNOWN_CA));
+    }
+}
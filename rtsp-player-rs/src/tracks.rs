@@ -0,0 +1,179 @@
+use gst_sdp::{SDPMediaRef, SDPMessageRef};
+
+/// One stream advertised in the SDP. Mirrors the fields of the Dart
+/// `VideoTrack`/`AudioTrack`/`SubtitleTrack` DTOs that an SDP can fill.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Track {
+    /// Index of the stream in the SDP, as used by `TrackSelection::Index`.
+    pub index: u32,
+    pub title: Option<String>,
+    pub language: Option<String>,
+    /// RTP encoding name, e.g. `H264` or `PCMA`.
+    pub codec: Option<String>,
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+    pub fps: Option<f64>,
+    /// Bits per second, from the `b=AS` line.
+    pub bitrate: Option<u32>,
+    pub samplerate: Option<u32>,
+    pub channels: Option<u32>,
+}
+
+/// All streams of a session, grouped the way the Dart `Tracks` DTO is.
+/// Metadata streams such as ONVIF `application` media end up in
+/// `subtitle`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tracks {
+    pub video: Vec<Track>,
+    pub audio: Vec<Track>,
+    pub subtitle: Vec<Track>,
+}
+
+/// Which stream of a kind to play.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrackSelection {
+    /// The first stream of the kind.
+    #[default]
+    Auto,
+    /// None, the stream is not set up at all.
+    No,
+    /// The stream with this SDP index.
+    Index(u32),
+}
+
+/// The current selection, mirrors the Dart `Track` DTO.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectedTracks {
+    pub video: TrackSelection,
+    pub audio: TrackSelection,
+}
+
+impl Tracks {
+    pub fn from_sdp(sdp: &SDPMessageRef) -> Self {
+        let mut tracks = Tracks::default();
+        for (index, media) in sdp.medias().enumerate() {
+            let track = track_from_media(index as u32, media);
+            match media.media() {
+                Some("video") => tracks.video.push(track),
+                Some("audio") => tracks.audio.push(track),
+                Some("application") | Some("text") => tracks.subtitle.push(track),
+                _ => (),
+            }
+        }
+        tracks
+    }
+
+    /// Whether the stream at `index`, of kind `media`, should be set up
+    /// for `selection`.
+    pub(crate) fn is_selected(&self, media: &str, index: u32, selection: TrackSelection) -> bool {
+        let of_kind = match media {
+            "video" => &self.video,
+            "audio" => &self.audio,
+            _ => return false,
+        };
+        match selection {
+            TrackSelection::Auto => of_kind.first().is_some_and(|track| track.index == index),
+            TrackSelection::No => false,
+            TrackSelection::Index(selected) => selected == index,
+        }
+    }
+}
+
+fn track_from_media(index: u32, media: &SDPMediaRef) -> Track {
+    let mut track = Track {
+        index,
+        title: media.information().map(str::to_string),
+        language: media.attribute_val("lang").map(str::to_string),
+        bitrate: media
+            .bandwidths()
+            .find(|bandwidth| bandwidth.bwtype() == Some("AS"))
+            .map(|bandwidth| bandwidth.value() * 1000),
+        fps: media
+            .attribute_val("framerate")
+            .and_then(|fps| fps.trim().parse().ok()),
+        ..Default::default()
+    };
+
+    // a=rtpmap:<pt> <encoding>/<clock rate>[/<channels>]
+    if let Some(rtpmap) = media.attribute_val("rtpmap") {
+        let mut parts = rtpmap
+            .split_once(' ')
+            .map_or("", |(_, encoding)| encoding)
+            .split('/');
+        track.codec = parts.next().map(str::to_string);
+        if media.media() == Some("audio") {
+            track.samplerate = parts.next().and_then(|rate| rate.parse().ok());
+            track.channels = Some(parts.next().and_then(|n| n.parse().ok()).unwrap_or(1));
+        }
+    }
+
+    // Cameras advertise the size either as a=x-dimensions:<w>,<h> or as
+    // a=cliprect:<top>,<left>,<bottom>,<right>.
+    let size = media
+        .attribute_val("x-dimensions")
+        .and_then(|dims| parse_numbers::<2>(dims))
+        .map(|[w, h]| (w, h))
+        .or_else(|| {
+            media
+                .attribute_val("cliprect")
+                .and_then(|rect| parse_numbers::<4>(rect))
+                .map(|[top, left, bottom, right]| (right - left, bottom - top))
+        });
+    if let Some((w, h)) = size {
+        track.w = Some(w);
+        track.h = Some(h);
+    }
+    track
+}
+
+fn parse_numbers<const N: usize>(text: &str) -> Option<[u32; N]> {
+    let numbers: Vec<u32> = text
+        .split(',')
+        .map(|n| n.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    numbers.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDP: &str = "v=0\r\n\
+o=- 0 0 IN IP4 127.0.0.1\r\n\
+s=Camera\r\n\
+t=0 0\r\n\
+m=video 0 RTP/AVP 96\r\n\
+b=AS:4000\r\n\
+a=rtpmap:96 H264/90000\r\n\
+a=framerate:25\r\n\
+a=x-dimensions:1920,1080\r\n\
+a=control:track1\r\n\
+m=audio 0 RTP/AVP 8\r\n\
+a=rtpmap:8 PCMA/8000/1\r\n\
+a=control:track2\r\n\
+m=application 0 RTP/AVP 107\r\n\
+a=rtpmap:107 vnd.onvif.metadata/90000\r\n\
+a=control:track3\r\n";
+
+    #[test]
+    fn tracks_from_camera_sdp() {
+        let sdp = gst_sdp::SDPMessage::parse_buffer(SDP.as_bytes()).unwrap();
+        let tracks = Tracks::from_sdp(&sdp);
+
+        assert_eq!(tracks.video.len(), 1);
+        let video = &tracks.video[0];
+        assert_eq!(video.codec.as_deref(), Some("H264"));
+        assert_eq!((video.w, video.h), (Some(1920), Some(1080)));
+        assert_eq!(video.fps, Some(25.0));
+        assert_eq!(video.bitrate, Some(4_000_000));
+
+        let audio = &tracks.audio[0];
+        assert_eq!(audio.index, 1);
+        assert_eq!((audio.samplerate, audio.channels), (Some(8000), Some(1)));
+        assert_eq!(tracks.subtitle[0].index, 2);
+
+        assert!(tracks.is_selected("video", 0, TrackSelection::Auto));
+        assert!(!tracks.is_selected("audio", 1, TrackSelection::No));
+        assert!(!tracks.is_selected("application", 2, TrackSelection::Auto));
+    }
+}
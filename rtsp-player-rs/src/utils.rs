@@ -1,11 +1,30 @@
-use gst::{Element, ElementFactory};
+use gst::{prelude::*, Element, ElementFactory};
 
-pub fn make_gs_element(factory_name: &str) -> anyhow::Result<Element> {
-    Ok(ElementFactory::make(factory_name).build()?)
+use crate::error::{PlayerError, Result};
+
+pub fn make_gs_element(factory_name: &str) -> Result<Element> {
+    ElementFactory::make(factory_name)
+        .build()
+        .map_err(|_| PlayerError::ElementMissing {
+            factory: factory_name.to_string(),
+        })
 }
 
-pub fn make_named_element(factory_name: &str, name: &str) -> anyhow::Result<Element> {
-    Ok(ElementFactory::make(factory_name)
+pub fn make_named_element(factory_name: &str, name: &str) -> Result<Element> {
+    ElementFactory::make(factory_name)
         .property("name", name)
-        .build()?)
+        .build()
+        .map_err(|_| PlayerError::ElementMissing {
+            factory: factory_name.to_string(),
+        })
+}
+
+/// Links `elements` in order, reporting the first pair that failed.
+pub fn link_many(elements: &[&Element]) -> Result<()> {
+    for pair in elements.windows(2) {
+        pair[0]
+            .link(pair[1])
+            .map_err(|_| PlayerError::link_failed(pair[0], pair[1]))?;
+    }
+    Ok(())
 }
@@ -0,0 +1,144 @@
+use gst::prelude::*;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::tracks::Tracks;
+
+/// Application message posted once a pre-rolled variant decoded its first
+/// frame and is ready to be swapped in.
+pub(crate) const VARIANT_READY: &str = "rtsp-player-variant-ready";
+
+/// A smaller variant is only picked once the render size is this far below
+/// it, so resizing around a threshold doesn't flap between streams.
+const DOWNSWITCH_MARGIN: f64 = 0.9;
+
+/// One encoding of a camera's stream, such as its main stream or substream.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamVariant {
+    pub uri: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl StreamVariant {
+    fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    fn covers(&self, width: u32, height: u32, margin: f64) -> bool {
+        self.width as f64 * margin >= width as f64 && self.height as f64 * margin >= height as f64
+    }
+}
+
+/// The variant to play at a render size of `width`x`height` physical
+/// pixels: the smallest one covering it, or the largest one.
+pub(crate) fn pick_variant(
+    variants: &[StreamVariant],
+    current: Option<usize>,
+    width: u32,
+    height: u32,
+) -> Option<usize> {
+    let mut by_area: Vec<usize> = (0..variants.len()).collect();
+    by_area.sort_by_key(|&index| variants[index].area());
+    let best = by_area
+        .iter()
+        .copied()
+        .find(|&index| variants[index].covers(width, height, 1.0))
+        .or_else(|| by_area.last().copied())?;
+
+    match current.filter(|&current| current < variants.len()) {
+        Some(current)
+            if variants[best].area() < variants[current].area()
+                && !variants[best].covers(width, height, DOWNSWITCH_MARGIN) =>
+        {
+            Some(current)
+        }
+        _ => Some(best),
+    }
+}
+
+/// A variant being pre-rolled next to the playing source. Its video branch
+/// decodes into a blocked pad until it is swapped in.
+pub(crate) struct PendingSwitch {
+    pub(crate) variant: usize,
+    pub(crate) source: gst::Element,
+    /// Everything added to the pipeline for it, `source` included.
+    pub(crate) elements: Vec<gst::Element>,
+    pub(crate) encoding: Option<String>,
+    pub(crate) tee: Option<gst::Element>,
+    /// The decoder's source pad and the probe holding its first frame.
+    pub(crate) ready_probe: Option<(gst::Pad, gst::PadProbeId)>,
+    /// Linked once swapped in, the playing source keeps the audio until
+    /// then.
+    pub(crate) audio_pad: Option<gst::Pad>,
+    /// From the pending source's SDP, published once swapped in.
+    pub(crate) tracks: Option<Tracks>,
+}
+
+impl PendingSwitch {
+    pub(crate) fn new(variant: usize, source: gst::Element) -> Self {
+        Self {
+            variant,
+            elements: vec![source.clone()],
+            source,
+            encoding: None,
+            tee: None,
+            ready_probe: None,
+            audio_pad: None,
+            tracks: None,
+        }
+    }
+
+    /// Whether `object` is, or is inside, one of the pending elements.
+    pub(crate) fn contains(&self, object: &gst::Object) -> bool {
+        self.elements.iter().any(|element| {
+            object == element.upcast_ref::<gst::Object>() || object.has_as_ancestor(element)
+        })
+    }
+
+    pub(crate) fn cancel(self, pipeline: &gst::Pipeline) {
+        for element in self.elements {
+            let _ = element.set_state(gst::State::Null);
+            if let Err(err) = pipeline.remove(&element) {
+                warn!("Failed to remove {}: {}", element.name(), err);
+            }
+        }
+    }
+}
+
+/// Drops what `depayloader` outputs up to the first keyframe, so the first
+/// frame the pending branch decodes, and swaps in, is a complete picture.
+pub(crate) fn drop_until_keyframe(depayloader: &gst::Element) {
+    let Some(src_pad) = depayloader.static_pad("src") else {
+        return;
+    };
+    src_pad.add_probe(gst::PadProbeType::BUFFER, |_, info| match info.buffer() {
+        Some(buffer) if buffer.flags().contains(gst::BufferFlags::DELTA_UNIT) => {
+            gst::PadProbeReturn::Drop
+        }
+        _ => gst::PadProbeReturn::Remove,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_smallest_covering_variant_with_hysteresis() {
+        let variant = |width, height| StreamVariant {
+            uri: format!("rtsp://camera/{}p", height),
+            width,
+            height,
+        };
+        let variants = [variant(1920, 1080), variant(640, 360)];
+
+        assert_eq!(pick_variant(&variants, None, 320, 180), Some(1));
+        assert_eq!(pick_variant(&variants, Some(1), 700, 400), Some(0));
+        assert_eq!(pick_variant(&variants, None, 3840, 2160), Some(0));
+        // Just below the substream, not yet worth switching down.
+        assert_eq!(pick_variant(&variants, Some(0), 630, 350), Some(0));
+        assert_eq!(pick_variant(&variants, Some(0), 500, 280), Some(1));
+        assert_eq!(pick_variant(&[], None, 500, 280), None);
+    }
+}
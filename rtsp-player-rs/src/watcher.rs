@@ -0,0 +1,77 @@
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+
+use gst::glib;
+
+/// A thread running a glib main loop, on which players watch their bus and
+/// run their timers. A `PlayerPool` shares one between all its players.
+pub(crate) struct BusWatcher {
+    context: glib::MainContext,
+    main_loop: glib::MainLoop,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl BusWatcher {
+    pub(crate) fn new(thread_name: &str) -> Self {
+        let context = glib::MainContext::new();
+        let main_loop = glib::MainLoop::new(Some(&context), false);
+        let thread_context = context.clone();
+        let thread_loop = main_loop.clone();
+        let thread = thread::Builder::new()
+            .name(thread_name.to_string())
+            .spawn(move || {
+                thread_context
+                    .with_thread_default(|| thread_loop.run())
+                    .expect("The watcher context is owned by another thread");
+            })
+            .expect("Failed to spawn the bus watcher thread");
+        Self {
+            context,
+            main_loop,
+            thread: Mutex::new(Some(thread)),
+        }
+    }
+
+    /// Dispatches messages of `bus` to `f` on the watcher thread until the
+    /// returned source is destroyed.
+    pub(crate) fn watch_bus(
+        &self,
+        bus: &gst::Bus,
+        f: impl FnMut(&gst::Bus, &gst::Message) -> glib::ControlFlow + Send + 'static,
+    ) -> glib::Source {
+        let source = bus.create_watch(None, glib::Priority::DEFAULT, f);
+        source.attach(Some(&self.context));
+        source
+    }
+
+    /// Calls `f` on the watcher thread after `delay`, and again every
+    /// `delay` while it returns `ControlFlow::Continue`.
+    pub(crate) fn add_timeout(
+        &self,
+        delay: std::time::Duration,
+        f: impl FnMut() -> glib::ControlFlow + Send + 'static,
+    ) -> glib::Source {
+        let source = glib::timeout_source_new(delay, None, glib::Priority::DEFAULT, f);
+        source.attach(Some(&self.context));
+        source
+    }
+}
+
+impl Drop for BusWatcher {
+    fn drop(&mut self) {
+        // Queued rather than called, in case the loop isn't running yet.
+        let main_loop = self.main_loop.clone();
+        glib::idle_source_new(None, glib::Priority::DEFAULT, move || {
+            main_loop.quit();
+            glib::ControlFlow::Break
+        })
+        .attach(Some(&self.context));
+        let Some(thread) = self.thread.lock().unwrap().take() else {
+            return;
+        };
+        // The last player may go away from one of its own callbacks.
+        if thread.thread().id() != thread::current().id() {
+            let _ = thread.join();
+        }
+    }
+}